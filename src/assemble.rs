@@ -1,10 +1,98 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
 
 use crate::{
-    components::{Charism, Delete, Replace},
+    components::{Charism, Delete, Fill, Param, Replace, Scale},
+    config::Installation,
+    error::UranusError,
+    package::{self, ModPackage, PackageOp},
+    paths, store,
     utils::join_path,
+    version::{self, Compatibility},
 };
 
+// fingerprints of the builds the built-in patterns were written against, as
+// printed by `uranus fingerprint`
+const BUILTIN_VERIFIED: &[&str] = &[];
+
+// gods offering the boons, as named by their loot, e.g. "ZeusUpgrade"
+const GODS: [&str; 8] = [
+    "Zeus",
+    "Poseidon",
+    "Athena",
+    "Ares",
+    "Aphrodite",
+    "Artemis",
+    "Dionysus",
+    "Demeter",
+];
+
+// group of the charisms scaling the resource drops
+const ECONOMY_GROUP: &str = "Economy";
+// multipliers of the resource drops chosen in the panel
+const MULTIPLIER_LABEL: &str = "Multiplier";
+const MULTIPLIER_MIN: f64 = 0.5;
+const MULTIPLIER_MAX: f64 = 10.0;
+const MULTIPLIER_STEP: f64 = 0.5;
+const MULTIPLIER_DEFAULT: f64 = 2.0;
+// amount of the resource added by the drop table of `ConsumableData.lua`,
+// captured as `value`, the lines of a table are indented below its name
+macro_rules! drop_resource {
+    ($table:literal, $resource:literal) => {
+        concat!(
+            r"(?m)^\t",
+            $table,
+            r"\s*=\s*\{(?:\n\t\t.*)*?\n\t\tAddResources\s*=\s*\{[^}]*?\b",
+            $resource,
+            r"\s*=\s*(?P<value>\d+)"
+        )
+    };
+}
+
+// drops of the resources as (name, description, amount), each amount is
+// matched once
+const ECONOMY: [(&str, &str, &str); 8] = [
+    (
+        "Darkness Multiplier",
+        "Multiply the Darkness of the room rewards.",
+        drop_resource!("RoomRewardMetaPointDrop", "MetaPoints"),
+    ),
+    (
+        "Gems Multiplier",
+        "Multiply the Gems of the room rewards.",
+        drop_resource!("GemDrop", "Gems"),
+    ),
+    (
+        "Chthonic Keys Multiplier",
+        "Multiply the Chthonic Keys of the room rewards.",
+        drop_resource!("LockKeyDrop", "LockKeys"),
+    ),
+    (
+        "Nectar Multiplier",
+        "Multiply the Nectar of the room rewards.",
+        drop_resource!("GiftDrop", "GiftPoints"),
+    ),
+    (
+        "Diamonds Multiplier",
+        "Multiply the Diamonds of the room rewards.",
+        drop_resource!("SuperGemDrop", "SuperGems"),
+    ),
+    (
+        "Ambrosia Multiplier",
+        "Multiply the Ambrosia of the room rewards.",
+        drop_resource!("SuperGiftDrop", "SuperGiftPoints"),
+    ),
+    (
+        "Titan Blood Multiplier",
+        "Multiply the Titan Blood of the room rewards.",
+        drop_resource!("SuperLockKeyDrop", "SuperLockKeys"),
+    ),
+    (
+        "Obols Multiplier",
+        "Multiply the Charon's Obols of the room rewards.",
+        r"(?m)^\tRoomRewardMoneyDrop\s*=\s*\{(?:\n\t\t.*)*?\n\t\tAddMoney\s*=\s*(?P<value>\d+)",
+    ),
+];
+
 pub struct Assemble<'a> {
     pub always_fishing_point_charism: Rc<RefCell<Charism<'a>>>,
     pub catch_better_fish_charism: Rc<RefCell<Charism<'a>>>,
@@ -12,12 +100,26 @@ pub struct Assemble<'a> {
     pub gifit_trait_quick_upgrade_charism: Rc<RefCell<Charism<'a>>>,
     pub free_store_exchange_charism: Rc<RefCell<Charism<'a>>>,
     pub always_hero_raity_trait_charism: Rc<RefCell<Charism<'a>>>,
-    pub hades_path: String,
+    pub force_olympian_charism: Rc<RefCell<Charism<'a>>>,
+    pub duo_boons_charism: Rc<RefCell<Charism<'a>>>,
+    pub legendary_boons_charism: Rc<RefCell<Charism<'a>>>,
+    pub more_boon_choices_charism: Rc<RefCell<Charism<'a>>>,
+    pub pom_of_power_levels_charism: Rc<RefCell<Charism<'a>>>,
+    // multipliers of the resource drops, in the order of `ECONOMY`
+    pub economy_charisms: Vec<Rc<RefCell<Charism<'a>>>>,
+    // installed mod packages, listed after the built-in charisms
+    pub packages: Vec<(ModPackage, Rc<RefCell<Charism<'a>>>)>,
+    // the Scripts folder and the mods applied to it
+    pub installation: Installation,
+    // backups of the installation of the Scripts folder
+    pub backup_home: String,
+    // build of the game of the installation
+    pub fingerprint: String,
 }
 
 impl<'a> Assemble<'a> {
     pub fn new() -> Self {
-        Assemble {
+        let assemble = Assemble {
             always_fishing_point_charism: Rc::new(RefCell::new(Charism::new(
                 "Fishing",
                 "Always Fishing Point",
@@ -48,19 +150,122 @@ impl<'a> Assemble<'a> {
                 "Always Hero Raity Trait",
                 "Always hero raity trait.",
             ))),
-            hades_path: "".to_string(),
+            force_olympian_charism: Rc::new(RefCell::new(
+                Charism::new(
+                    "BoonControl",
+                    "Force Olympian",
+                    "The boon rooms are offered by the chosen god.",
+                )
+                .with_param(Param::choice("God", &GODS, 0)),
+            )),
+            duo_boons_charism: Rc::new(RefCell::new(
+                Charism::new(
+                    "BoonControl",
+                    "Duo Boons",
+                    "Offer duo boons whenever their requirements are met.",
+                )
+                .with_param(Param::new("Chance", 0.1, 1.0, 0.1, 1.0)),
+            )),
+            legendary_boons_charism: Rc::new(RefCell::new(
+                Charism::new(
+                    "BoonControl",
+                    "Legendary Boons",
+                    "Offer legendary boons whenever their requirements are met.",
+                )
+                .with_param(Param::new("Chance", 0.1, 1.0, 0.1, 1.0)),
+            )),
+            more_boon_choices_charism: Rc::new(RefCell::new(
+                Charism::new(
+                    "BoonControl",
+                    "More Boon Choices",
+                    "Raise the number of boons to choose from.",
+                )
+                .with_param(Param::new("Choices", 3.0, 6.0, 1.0, 4.0)),
+            )),
+            pom_of_power_levels_charism: Rc::new(RefCell::new(
+                Charism::new(
+                    "BoonControl",
+                    "Pom Of Power Levels",
+                    "Make Pom of Power grant more levels.",
+                )
+                .with_param(Param::new("Levels", 1.0, 5.0, 1.0, 2.0)),
+            )),
+            economy_charisms: ECONOMY
+                .iter()
+                .map(|(name, description, _)| {
+                    let charism =
+                        Charism::new(ECONOMY_GROUP, *name, *description).with_param(Param::new(
+                            MULTIPLIER_LABEL,
+                            MULTIPLIER_MIN,
+                            MULTIPLIER_MAX,
+                            MULTIPLIER_STEP,
+                            MULTIPLIER_DEFAULT,
+                        ));
+                    Rc::new(RefCell::new(charism))
+                })
+                .collect(),
+            packages: package::installed()
+                .into_iter()
+                .map(|package| {
+                    let mut charism = Charism::new(
+                        package.group.clone(),
+                        package.name.clone(),
+                        package.description.clone(),
+                    );
+                    charism.verified = package.all_verified();
+                    (package, Rc::new(RefCell::new(charism)))
+                })
+                .collect(),
+            installation: Installation::default(),
+            backup_home: String::new(),
+            fingerprint: String::new(),
+        };
+        for charism in assemble.builtin_charisms() {
+            charism.borrow_mut().verified = BUILTIN_VERIFIED
+                .iter()
+                .map(|value| value.to_string())
+                .collect();
+        }
+        assemble
+    }
+
+    pub fn set_installation(&mut self, installation: Installation) {
+        self.backup_home = paths::backup_home(&installation.hades_path);
+        for charism in self.charisms() {
+            let mut charism = charism.borrow_mut();
+            charism.set_backup_home(&self.backup_home);
+            if let Some(param) = &charism.param {
+                let value = installation.params.get(charism.name.as_ref());
+                param.set(value.copied().unwrap_or(param.default));
+            }
         }
+        self.fingerprint = version::fingerprint(&installation.hades_path, &self.backup_home)
+            .unwrap_or_else(|err| {
+                log::error!(
+                    "Assemble: fingerprint `{}` failed, {}",
+                    installation.hades_path,
+                    err
+                );
+                String::new()
+            });
+        log::info!(
+            "Assemble: `{}` fingerprint {}.",
+            installation.hades_path,
+            self.fingerprint
+        );
+        self.installation = installation;
     }
 
-    pub fn set_hades_path(&mut self, hades_path: String) {
-        self.hades_path = hades_path;
+    /// The Scripts folder of the installation.
+    pub fn hades_path(&self) -> &str {
+        &self.installation.hades_path
     }
 
     pub fn assemble_always_fishing_point(&self) {
         let binding = self.always_fishing_point_charism.clone();
         let always_fishing_point_charism = binding.borrow();
         always_fishing_point_charism.add(Delete::new(
-            join_path(&self.hades_path, "RoomManager.lua"),
+            join_path(self.hades_path(), "RoomManager.lua"),
             r"and IsFishingEligible\( currentRun, currentRoom \)",
         ));
     }
@@ -69,7 +274,7 @@ impl<'a> Assemble<'a> {
         let binding = self.catch_better_fish_charism.clone();
         let catch_better_fish_charism = binding.borrow();
         catch_better_fish_charism.add(Replace::new(
-            join_path(&self.hades_path, "FishingData.lua"),
+            join_path(self.hades_path(), "FishingData.lua"),
             r"Weight = 0.05",
             "Weight = 10",
         ));
@@ -80,17 +285,17 @@ impl<'a> Assemble<'a> {
         let easier_to_pick_up_charism = binding.borrow();
         easier_to_pick_up_charism
             .add(Replace::new(
-                join_path(&self.hades_path, "FishingData.lua"),
+                join_path(self.hades_path(), "FishingData.lua"),
                 r"NumFakeDunks.*?\},",
                 "NumFakeDunks = { Min = 0, Max = 0 },",
             ))
             .add(Replace::new(
-                join_path(&self.hades_path, "FishingData.lua"),
+                join_path(self.hades_path(), "FishingData.lua"),
                 r"GoodInterval.*?,",
                 "GoodInterval = 3,",
             ))
             .add(Replace::new(
-                join_path(&self.hades_path, "FishingData.lua"),
+                join_path(self.hades_path(), "FishingData.lua"),
                 r"PerfectInterval.*?,",
                 "PerfectInterval = 1,",
             ));
@@ -100,7 +305,7 @@ impl<'a> Assemble<'a> {
         let binding = self.gifit_trait_quick_upgrade_charism.clone();
         let gifit_trait_quick_upgrade_charism = binding.borrow();
         gifit_trait_quick_upgrade_charism.add(Replace::new(
-            join_path(&self.hades_path, "TraitData.lua"),
+            join_path(self.hades_path(), "TraitData.lua"),
             r"ChamberThresholds.*?\},",
             "ChamberThresholds =  { 1, 1 },",
         ));
@@ -110,7 +315,7 @@ impl<'a> Assemble<'a> {
         let binding = self.free_store_exchange_charism.clone();
         let free_store_exchange_charism = binding.borrow();
         free_store_exchange_charism.add(Replace::new(
-            join_path(&self.hades_path, "StoreData.lua"),
+            join_path(self.hades_path(), "StoreData.lua"),
             r"CostAmount = ",
             "CostAmount = -",
         ));
@@ -150,22 +355,22 @@ impl<'a> Assemble<'a> {
 
         always_hero_raity_trait_charism
         .add(Replace::new(
-            join_path(&self.hades_path,"TraitScripts.lua"), 
+            join_path(self.hades_path(),"TraitScripts.lua"), 
              r"elseif rarityTable.Legendary.*?then",
              "elseif rarityTable.Heroic[upgradeData.ItemName] then"
             ))
         .add(Replace::new(
-            join_path(&self.hades_path, "TraitScripts.lua"),
+            join_path(self.hades_path(), "TraitScripts.lua"),
             r"if validRarities.Legendary and lootData.RarityChances.Legendary and RandomChance\( lootData.RarityChances.Legendary \)",
             "if validRarities.Legendary"
         ))
         .add(Replace::new(
-            join_path(&self.hades_path, "TraitScripts.lua"),
+            join_path(self.hades_path(), "TraitScripts.lua"),
             r"elseif validRarities.Heroic and lootData.RarityChances.Heroic and RandomChance\( lootData.RarityChances.Heroic \)",
             "elseif validRarities.Heroic"
         ))
         .add(Replace::new(
-            join_path(&self.hades_path, "TraitScripts.lua"),
+            join_path(self.hades_path(), "TraitScripts.lua"),
             // r"if validRarities.Rare and lootData.RarityChances.Rare then\n.*?\n.*?Rare \)",
             r"\tif validRarities.Rare.*?\n.*?\n.*?\)",
             r#"        if validRarities.Heroic  then
@@ -173,7 +378,7 @@ impl<'a> Assemble<'a> {
             chosenUpgrade = GetRandomValue( rarityTable.Heroic )"#,
         ))
         .add(Replace::new(
-            join_path(&self.hades_path, "TraitScripts.lua"),
+            join_path(self.hades_path(), "TraitScripts.lua"),
             r#"elseif validRarities.Heroic.*?\n.*?\n.*?\)"#,
             r#"elseif validRarities.Rare and lootData.RarityChances.Rare then
             chosenRarity = "Rare"
@@ -181,6 +386,280 @@ impl<'a> Assemble<'a> {
         ));
     }
 
+    pub fn assemble_force_olympian(&self) {
+        let binding = self.force_olympian_charism.clone();
+        let force_olympian_charism = binding.borrow();
+        if let Some(param) = force_olympian_charism.param.clone() {
+            // only the random pick among the eligible gods, the loot asked by
+            // the caller, e.g. a forced Hermes, and an excluded god are kept
+            force_olympian_charism.add(Fill::exact(
+                join_path(self.hades_path(), "RunManager.lua"),
+                r"(?m)(^function ChooseLoot\( excludeLootNames, forceLootName \)(?:\n\t.*)*?\n\tlocal chosenLootName = forceLootName or )GetRandomValue\( eligibleLootNames \)",
+                "${1}( Contains( eligibleLootNames, \"{value}Upgrade\" ) and \"{value}Upgrade\" ) or GetRandomValue( eligibleLootNames )",
+                param,
+                1,
+            ));
+        }
+    }
+
+    pub fn assemble_duo_boons(&self) {
+        let binding = self.duo_boons_charism.clone();
+        let duo_boons_charism = binding.borrow();
+        if let Some(param) = duo_boons_charism.param.clone() {
+            // the chances of the gods only, not of Hermes, the hammers or the poms
+            duo_boons_charism.add(Fill::exact(
+                join_path(self.hades_path(), "HeroData.lua"),
+                r"(?m)(^\t\tBoonData\s*=\s*\{(?:\n\t\t\t.*)*?\n\t\t\tDuoChance\s*=\s*)[\d.]+",
+                "${1}{value}",
+                param,
+                1,
+            ));
+        }
+    }
+
+    pub fn assemble_legendary_boons(&self) {
+        let binding = self.legendary_boons_charism.clone();
+        let legendary_boons_charism = binding.borrow();
+        if let Some(param) = legendary_boons_charism.param.clone() {
+            legendary_boons_charism.add(Fill::exact(
+                join_path(self.hades_path(), "HeroData.lua"),
+                r"(?m)(^\t\tBoonData\s*=\s*\{(?:\n\t\t\t.*)*?\n\t\t\tLegendaryChance\s*=\s*)[\d.]+",
+                "${1}{value}",
+                param,
+                1,
+            ));
+        }
+    }
+
+    pub fn assemble_more_boon_choices(&self) {
+        let binding = self.more_boon_choices_charism.clone();
+        let more_boon_choices_charism = binding.borrow();
+        if let Some(param) = more_boon_choices_charism.param.clone() {
+            more_boon_choices_charism.add(Fill::exact(
+                join_path(self.hades_path(), "TraitScripts.lua"),
+                r"(function GetTotalLootChoices\(\)\s*return )\d+",
+                "${1}{value}",
+                param,
+                1,
+            ));
+        }
+    }
+
+    pub fn assemble_pom_of_power_levels(&self) {
+        let binding = self.pom_of_power_levels_charism.clone();
+        let pom_of_power_levels_charism = binding.borrow();
+        if let Some(param) = pom_of_power_levels_charism.param.clone() {
+            pom_of_power_levels_charism.add(Fill::exact(
+                join_path(self.hades_path(), "LootData.lua"),
+                r"(?m)(^\tStackUpgrade\s*=\s*\{(?:\n\t\t.*)*?\n\t\tStackNum\s*=\s*)\d+",
+                "${1}{value}",
+                param,
+                1,
+            ));
+        }
+    }
+
+    pub fn assemble_economy(&self) {
+        for ((_, _, from), charism) in ECONOMY.iter().zip(&self.economy_charisms) {
+            let charism = charism.borrow();
+            let factor = match &charism.param {
+                Some(param) => param.value(),
+                None => continue,
+            };
+            charism.add(Scale::exact(
+                join_path(self.hades_path(), "ConsumableData.lua"),
+                from,
+                factor,
+                1,
+            ));
+        }
+    }
+
+    pub fn assemble_package(&self, package: &ModPackage, charism: &Rc<RefCell<Charism<'a>>>) {
+        let binding = charism.clone();
+        let charism = binding.borrow();
+        for op in package.ops_for(&self.fingerprint) {
+            match op {
+                PackageOp::Replace {
+                    file,
+                    from,
+                    to,
+                    count,
+                } => {
+                    let file_path = join_path(self.hades_path(), file);
+                    charism.add(match *count {
+                        Some(count) => Replace::exact(file_path, from, to, count),
+                        None => Replace::new(file_path, from, to),
+                    });
+                }
+                PackageOp::Delete { file, from, count } => {
+                    let file_path = join_path(self.hades_path(), file);
+                    charism.add(match *count {
+                        Some(count) => Delete::exact(file_path, from, count),
+                        None => Delete::new(file_path, from),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Add an installed package, a package of an existing name is
+    /// skipped until the next start.
+    pub fn add_package(&mut self, package: ModPackage) -> bool {
+        if self
+            .charisms()
+            .iter()
+            .any(|charism| charism.borrow().name == package.name)
+        {
+            return false;
+        }
+        let mut charism = Charism::new(
+            package.group.clone(),
+            package.name.clone(),
+            package.description.clone(),
+        );
+        charism.verified = package.all_verified();
+        charism.set_backup_home(&self.backup_home);
+        let charism = Rc::new(RefCell::new(charism));
+        if !self.hades_path().is_empty() {
+            self.assemble_package(&package, &charism);
+        }
+        self.packages.push((package, charism));
+        true
+    }
+
+    /// Whether the charism is verified on the build of the installation, by
+    /// its own fingerprints or those the user verified it on.
+    pub fn compatibility(&self, charism: &Charism, verified_by_user: &[String]) -> Compatibility {
+        let verified = charism
+            .verified
+            .iter()
+            .map(String::as_str)
+            .chain(verified_by_user.iter().map(String::as_str));
+        Compatibility::of(verified, &self.fingerprint)
+    }
+
+    /// All charisms in display order.
+    pub fn charisms(&self) -> Vec<Rc<RefCell<Charism<'a>>>> {
+        let mut charisms = self.builtin_charisms();
+        charisms.extend(self.packages.iter().map(|(_, charism)| charism.clone()));
+        charisms
+    }
+
+    /// The charisms which are not from packages, in display order.
+    fn builtin_charisms(&self) -> Vec<Rc<RefCell<Charism<'a>>>> {
+        let mut charisms = vec![
+            self.always_fishing_point_charism.clone(),
+            self.catch_better_fish_charism.clone(),
+            self.easier_to_pick_up_charism.clone(),
+            self.gifit_trait_quick_upgrade_charism.clone(),
+            self.free_store_exchange_charism.clone(),
+            self.always_hero_raity_trait_charism.clone(),
+            self.force_olympian_charism.clone(),
+            self.duo_boons_charism.clone(),
+            self.legendary_boons_charism.clone(),
+            self.more_boon_choices_charism.clone(),
+            self.pom_of_power_levels_charism.clone(),
+        ];
+        charisms.extend(self.economy_charisms.iter().cloned());
+        charisms
+    }
+
+    /// Groups of the charisms in display order.
+    pub fn groups(&self) -> Vec<String> {
+        let mut groups: Vec<String> = Vec::new();
+        for charism in self.charisms() {
+            let charism = charism.borrow();
+            if !groups.iter().any(|group| *group == charism.group) {
+                groups.push(charism.group.to_string());
+            }
+        }
+        groups
+    }
+
+    /// Indexes of the charisms which belong to the group.
+    pub fn group_indexes(&self, group: &str) -> Vec<usize> {
+        self.charisms()
+            .iter()
+            .enumerate()
+            .filter(|(_, charism)| charism.borrow().group == group)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// The charism at index and the checked charisms which share a file with
+    /// it, or with one of them, in display order.
+    fn sharing_indexes(&self, index: usize, checked: &[bool]) -> Vec<usize> {
+        let charisms = self.charisms();
+        let mut indexes = vec![index];
+        let mut files: HashSet<String> = charisms[index].borrow().files().into_iter().collect();
+        while let Some(found) = (0..charisms.len()).find(|other| {
+            !indexes.contains(other)
+                && checked.get(*other) == Some(&true)
+                && charisms[*other]
+                    .borrow()
+                    .files()
+                    .iter()
+                    .any(|file| files.contains(file))
+        }) {
+            files.extend(charisms[found].borrow().files());
+            indexes.push(found);
+        }
+        indexes.sort_unstable();
+        indexes
+    }
+
+    /// Apply the charism at index, or roll it back. A rollback restores whole
+    /// files, so the checked charisms sharing its files are rolled back with it
+    /// and applied again in display order. Returns the charisms which failed,
+    /// they are left rolled back. The backups of the files are written at once
+    /// when it ends.
+    pub fn switch(&self, index: usize, value: bool, checked: &[bool]) -> Vec<(usize, UranusError)> {
+        store::batch(|| self.switch_charisms(index, value, checked))
+            .unwrap_or_else(|err| vec![(index, err)])
+    }
+
+    fn switch_charisms(
+        &self,
+        index: usize,
+        value: bool,
+        checked: &[bool],
+    ) -> Vec<(usize, UranusError)> {
+        let charisms = self.charisms();
+        if index >= charisms.len() {
+            return Vec::new();
+        }
+        let indexes = self.sharing_indexes(index, checked);
+        let mut applying: Vec<usize> = indexes
+            .iter()
+            .copied()
+            .filter(|other| *other != index || value)
+            .collect();
+        let mut failures = Vec::new();
+        loop {
+            for other in &indexes {
+                if let Err(err) = charisms[*other].borrow().rollback(false) {
+                    failures.push((*other, err));
+                    return failures;
+                }
+            }
+            let failure = applying.iter().find_map(|other| {
+                let charism = charisms[*other].borrow();
+                charism.apply_changes().err().map(|err| (*other, err))
+            });
+            match failure {
+                Some((other, err)) => {
+                    // the others are applied again without it
+                    applying.retain(|value| *value != other);
+                    failures.push((other, err));
+                }
+                None => break,
+            }
+        }
+        log::info!("<-{}->", charisms[index].borrow().name);
+        failures
+    }
+
     pub fn assemble_all(&self) {
         self.assemble_always_fishing_point();
         self.assemble_catch_better_fish();
@@ -188,6 +667,15 @@ impl<'a> Assemble<'a> {
         self.assemble_gifit_trait_quick_upgrade();
         self.assemble_free_store_exchange();
         self.assemble_always_hero_raity_trait();
+        self.assemble_force_olympian();
+        self.assemble_duo_boons();
+        self.assemble_legendary_boons();
+        self.assemble_more_boon_choices();
+        self.assemble_pom_of_power_levels();
+        self.assemble_economy();
+        for (package, charism) in &self.packages {
+            self.assemble_package(package, charism);
+        }
     }
 }
 
@@ -196,3 +684,6 @@ impl Default for Assemble<'_> {
         Assemble::new()
     }
 }
+
+#[cfg(test)]
+mod tests;
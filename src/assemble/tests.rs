@@ -0,0 +1,415 @@
+//! Round trips of the built-in charisms on the miniature Scripts folder of
+//! `tests/fixtures/Scripts`: each charism must turn the files into the golden
+//! files of `tests/fixtures/expected/<charism>/`, leave the others alone, and
+//! roll back to byte-identical files. Run with `URANUS_BLESS=1` to write the
+//! golden files again after changing a pattern, then review their diff.
+//!
+//! The fixtures are excerpts written after the Scripts of Hades v1.38, the
+//! last patch, keeping only the tables and functions the patterns touch with
+//! their formatting. They are not copies of the game files, so a pattern
+//! passing here is checked on the game before its fingerprint is verified.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Mutex, MutexGuard};
+
+use super::Assemble;
+use crate::components::Charism;
+use crate::config::Installation;
+use crate::vanilla;
+use crate::version::{Compatibility, KEY_FILES};
+use crate::{paths, store};
+
+const BLESS_ENV: &str = "URANUS_BLESS";
+
+// the index of the store is read and written by every test
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+}
+
+fn read(path: &Path) -> Vec<u8> {
+    fs::read(path).unwrap_or_else(|err| panic!("read `{}` failed, {}", path.display(), err))
+}
+
+/// A fresh copy of the fixture Scripts folder for the test, the backups and
+/// snapshots are kept in a temporary home instead of the user's.
+fn scripts_copy(test: &str) -> (MutexGuard<'static, ()>, String) {
+    let root = env::temp_dir().join(format!("uranus-tests-{}", process::id()));
+    paths::set_home_for_tests(root.join("home"));
+    // a failed test must not fail the others
+    let guard = STORE_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+
+    let dir = root.join(test).join("Scripts");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    for name in KEY_FILES {
+        fs::copy(fixtures_dir().join("Scripts").join(name), dir.join(name)).unwrap();
+    }
+    (guard, dir.to_string_lossy().to_string())
+}
+
+fn assemble(hades_path: &str) -> Assemble<'static> {
+    let mut assemble = Assemble::new();
+    assemble.set_installation(Installation::new(hades_path.to_string()));
+    assemble.assemble_all();
+    assemble
+}
+
+/// Directory of the golden files of the charism, e.g. "expected/catch-better-fish".
+fn expected_dir(charism: &Charism) -> PathBuf {
+    let slug = charism.name.to_lowercase().replace(' ', "-");
+    fixtures_dir().join("expected").join(slug)
+}
+
+/// Keep the files changed by the charism as its golden files.
+fn bless(hades_path: &str, expected: &Path) {
+    let _ = fs::remove_dir_all(expected);
+    fs::create_dir_all(expected).unwrap();
+    for name in KEY_FILES {
+        let actual = read(&Path::new(hades_path).join(name));
+        if actual != read(&fixtures_dir().join("Scripts").join(name)) {
+            fs::write(expected.join(name), actual).unwrap();
+        }
+    }
+}
+
+/// Compare every key file with its golden file, or with the fixture if the
+/// golden directory has none.
+fn assert_files(hades_path: &str, expected: Option<&Path>) {
+    for name in KEY_FILES {
+        let actual = read(&Path::new(hades_path).join(name));
+        let golden = expected
+            .map(|dir| dir.join(name))
+            .filter(|path| path.is_file())
+            .unwrap_or_else(|| fixtures_dir().join("Scripts").join(name));
+        assert!(
+            actual == read(&golden),
+            "`{}` differs from `{}`:\n{}",
+            name,
+            golden.display(),
+            String::from_utf8_lossy(&actual)
+        );
+    }
+}
+
+/// Apply the charism, check the golden files, then roll it back.
+fn round_trip(hades_path: &str, charism: &Charism) {
+    assert_eq!(
+        charism.is_applied().unwrap(),
+        Some(false),
+        "{} applied before",
+        charism.name
+    );
+    charism.apply().unwrap();
+
+    let expected = expected_dir(charism);
+    if env::var_os(BLESS_ENV).is_some() {
+        bless(hades_path, &expected);
+    }
+    assert!(
+        expected.is_dir(),
+        "no golden files of {}, run with {}=1",
+        charism.name,
+        BLESS_ENV
+    );
+    assert_files(hades_path, Some(&expected));
+    assert_eq!(
+        charism.is_applied().unwrap(),
+        Some(true),
+        "{} not applied",
+        charism.name
+    );
+
+    charism.rollback(true).unwrap();
+    assert_files(hades_path, None);
+    assert_eq!(
+        charism.is_applied().unwrap(),
+        Some(false),
+        "{} still applied",
+        charism.name
+    );
+}
+
+fn index_of(assemble: &Assemble, name: &str) -> usize {
+    assemble
+        .charisms()
+        .iter()
+        .position(|charism| charism.borrow().name == name)
+        .unwrap_or_else(|| panic!("no charism {}", name))
+}
+
+/// Check the charisms in order as the panel does, returns the checked ones.
+fn switch_on(assemble: &Assemble, indexes: &[usize]) -> Vec<bool> {
+    let mut checked = vec![false; assemble.charisms().len()];
+    for &index in indexes {
+        let failures = assemble.switch(index, true, &checked);
+        assert!(failures.is_empty(), "switch on failed, {:?}", failures);
+        checked[index] = true;
+    }
+    checked
+}
+
+fn assert_applied(assemble: &Assemble, checked: &[bool]) {
+    for (charism, checked) in assemble.charisms().iter().zip(checked) {
+        let charism = charism.borrow();
+        assert_eq!(
+            charism.is_applied().unwrap(),
+            Some(*checked),
+            "{} applied is not {}",
+            charism.name,
+            checked
+        );
+    }
+}
+
+macro_rules! round_trip_tests {
+    ($($test:ident: $charism:ident,)*) => {
+        $(
+            #[test]
+            fn $test() {
+                let (_guard, hades_path) = scripts_copy(stringify!($test));
+                let assemble = assemble(&hades_path);
+                let charism = assemble.$charism.clone();
+                round_trip(&hades_path, &charism.borrow());
+            }
+        )*
+    };
+}
+
+round_trip_tests! {
+    always_fishing_point: always_fishing_point_charism,
+    catch_better_fish: catch_better_fish_charism,
+    easier_to_pick_up: easier_to_pick_up_charism,
+    gifit_trait_quick_upgrade: gifit_trait_quick_upgrade_charism,
+    free_store_exchange: free_store_exchange_charism,
+    always_hero_raity_trait: always_hero_raity_trait_charism,
+    force_olympian: force_olympian_charism,
+    duo_boons: duo_boons_charism,
+    legendary_boons: legendary_boons_charism,
+    more_boon_choices: more_boon_choices_charism,
+    pom_of_power_levels: pom_of_power_levels_charism,
+}
+
+#[test]
+fn economy_charisms() {
+    let (_guard, hades_path) = scripts_copy("economy_charisms");
+    let assemble = assemble(&hades_path);
+    for charism in &assemble.economy_charisms {
+        round_trip(&hades_path, &charism.borrow());
+    }
+}
+
+#[test]
+fn economy_charism_follows_its_param() {
+    let (_guard, hades_path) = scripts_copy("economy_charism_follows_its_param");
+    let mut installation = Installation::new(hades_path.clone());
+    installation
+        .params
+        .insert("Obols Multiplier".to_string(), 1.5);
+    let mut assemble = Assemble::new();
+    assemble.set_installation(installation);
+    assemble.assemble_all();
+    let charism = assemble.economy_charisms[7].clone();
+    let charism = charism.borrow();
+    let content = || read(&Path::new(&hades_path).join("ConsumableData.lua"));
+
+    charism.apply().unwrap();
+    let applied = String::from_utf8(content()).unwrap();
+    assert!(applied.contains("AddMoney = 150,"));
+    // only the room reward is scaled
+    assert!(applied.contains("AddMoney = 5,") && applied.contains("AddMoney = 10,"));
+    // applied again from the origin file, not from the scaled one
+    charism.param.as_ref().unwrap().set(3.0);
+    charism.apply().unwrap();
+    let applied = String::from_utf8(content()).unwrap();
+    assert!(applied.contains("AddMoney = 300,") && applied.contains("AddMoney = 5,"));
+
+    charism.rollback(true).unwrap();
+    assert_files(&hades_path, None);
+}
+
+#[test]
+fn multiplier_of_one_is_not_reported_unapplied() {
+    let (_guard, hades_path) = scripts_copy("multiplier_of_one_is_not_reported_unapplied");
+    let mut installation = Installation::new(hades_path.clone());
+    installation
+        .params
+        .insert("Obols Multiplier".to_string(), 1.0);
+    let mut assemble = Assemble::new();
+    assemble.set_installation(installation);
+    assemble.assemble_all();
+    let charism = assemble.economy_charisms[7].clone();
+    let charism = charism.borrow();
+
+    charism.apply().unwrap();
+    assert_files(&hades_path, None);
+    // the files are vanilla either way
+    assert_eq!(charism.is_applied().unwrap(), None);
+    charism.rollback(true).unwrap();
+    assert_eq!(charism.is_applied().unwrap(), None);
+}
+
+#[test]
+fn force_olympian_follows_the_chosen_god() {
+    let (_guard, hades_path) = scripts_copy("force_olympian_follows_the_chosen_god");
+    let mut installation = Installation::new(hades_path.clone());
+    installation
+        .params
+        .insert("Force Olympian".to_string(), 7.0);
+    let mut assemble = Assemble::new();
+    assemble.set_installation(installation);
+    assemble.assemble_all();
+    let charism = assemble.force_olympian_charism.clone();
+    let charism = charism.borrow();
+    let content = || read(&Path::new(&hades_path).join("RunManager.lua"));
+
+    charism.apply().unwrap();
+    let applied = String::from_utf8(content()).unwrap();
+    assert!(applied.contains("Contains( eligibleLootNames, \"DemeterUpgrade\" )"));
+    // the loot asked by the caller comes first
+    assert!(applied.contains("local chosenLootName = forceLootName or ( Contains("));
+    assert!(!applied.contains("forceLootName = forceLootName or \""));
+    charism.param.as_ref().unwrap().set(2.0);
+    charism.apply().unwrap();
+    let applied = String::from_utf8(content()).unwrap();
+    assert!(applied.contains("Contains( eligibleLootNames, \"AthenaUpgrade\" )"));
+    assert!(!applied.contains("DemeterUpgrade"));
+
+    charism.rollback(true).unwrap();
+    assert_files(&hades_path, None);
+}
+
+#[test]
+fn charisms_sharing_a_file_stay_applied() {
+    let (_guard, hades_path) = scripts_copy("charisms_sharing_a_file_stay_applied");
+    let assemble = assemble(&hades_path);
+    let darkness = index_of(&assemble, "Darkness Multiplier");
+    let gems = index_of(&assemble, "Gems Multiplier");
+
+    let mut checked = switch_on(&assemble, &[gems, darkness]);
+    assert_applied(&assemble, &checked);
+    // the other one is applied again after the rollback
+    assert!(assemble.switch(gems, false, &checked).is_empty());
+    checked[gems] = false;
+    assert_applied(&assemble, &checked);
+    assert!(assemble.switch(darkness, false, &checked).is_empty());
+    assert_files(&hades_path, None);
+}
+
+#[test]
+fn boon_choices_stay_applied_with_hero_rarity() {
+    let (_guard, hades_path) = scripts_copy("boon_choices_stay_applied_with_hero_rarity");
+    let assemble = assemble(&hades_path);
+    let hero = index_of(&assemble, "Always Hero Raity Trait");
+    let choices = index_of(&assemble, "More Boon Choices");
+
+    let mut checked = switch_on(&assemble, &[hero, choices]);
+    assert_applied(&assemble, &checked);
+    assert!(assemble.switch(hero, false, &checked).is_empty());
+    checked[hero] = false;
+    assert_applied(&assemble, &checked);
+    // as applied alone
+    assert_files(
+        &hades_path,
+        Some(&expected_dir(&assemble.more_boon_choices_charism.borrow())),
+    );
+    assert!(assemble.switch(choices, false, &checked).is_empty());
+    assert_files(&hades_path, None);
+}
+
+#[test]
+fn all_charisms_roll_back_together() {
+    let (_guard, hades_path) = scripts_copy("all_charisms_roll_back_together");
+    let assemble = assemble(&hades_path);
+    let indexes: Vec<usize> = (0..assemble.charisms().len()).collect();
+    let mut checked = switch_on(&assemble, &indexes);
+    assert_applied(&assemble, &checked);
+    for index in indexes.into_iter().rev() {
+        assert!(assemble.switch(index, false, &checked).is_empty());
+        checked[index] = false;
+    }
+    assert_files(&hades_path, None);
+}
+
+#[test]
+fn vanilla_restores_all_charisms() {
+    let (_guard, hades_path) = scripts_copy("vanilla_restores_all_charisms");
+    let assemble = assemble(&hades_path);
+    for charism in assemble.charisms() {
+        charism.borrow().apply().unwrap();
+    }
+
+    let report = vanilla::restore(&hades_path).unwrap();
+    assert_files(&hades_path, None);
+    assert_eq!(report.restored.len(), KEY_FILES.len());
+    assert!(report.removed.is_empty());
+    // nothing left to restore
+    assert!(vanilla::restore(&hades_path).unwrap().restored.is_empty());
+}
+
+#[test]
+fn builtin_charisms_can_be_marked_verified() {
+    let (_guard, hades_path) = scripts_copy("builtin_charisms_can_be_marked_verified");
+    let assemble = assemble(&hades_path);
+    let charisms = assemble.charisms();
+    let charism = charisms[index_of(&assemble, "Catch Better Fish")].borrow();
+    assert_eq!(charism.verified, super::BUILTIN_VERIFIED);
+
+    let marked = vec![assemble.fingerprint.clone()];
+    assert_eq!(
+        assemble.compatibility(&charism, &marked),
+        Compatibility::Verified
+    );
+    let other = vec!["0000000000000000".to_string()];
+    assert_eq!(
+        assemble.compatibility(&charism, &other),
+        Compatibility::Untested
+    );
+}
+
+#[test]
+fn vanilla_restores_unassigned_legacy_backups() {
+    let (_guard, hades_path) = scripts_copy("vanilla_restores_unassigned_legacy_backups");
+    // a file no other test folder has, so no folder takes its legacy backup
+    let name = "LegacyData.lua";
+    fs::write(Path::new(&hades_path).join(name), "-- patched\n").unwrap();
+    let legacy_key = Path::new(&paths::legacy_backup_home()).join(name);
+    store::backup(&legacy_key.to_string_lossy(), b"-- vanilla\n").unwrap();
+
+    let report = vanilla::restore(&hades_path).unwrap();
+    assert_eq!(report.legacy, vec![name.to_string()]);
+    assert!(report.restored.is_empty());
+    assert_eq!(
+        read(&Path::new(&hades_path).join(name)),
+        b"-- vanilla\n".to_vec()
+    );
+    // the backup was left to no installation
+    assert!(store::backup_hash(&legacy_key.to_string_lossy())
+        .unwrap()
+        .is_some());
+}
+
+#[test]
+fn store_batch_writes_the_index_when_it_ends() {
+    let (_guard, hades_path) = scripts_copy("store_batch_writes_the_index_when_it_ends");
+    let index = store::store_dir().join("index.toml");
+    let key = Path::new(&hades_path).join("BatchData.lua");
+    let key = key.to_string_lossy();
+    let before = fs::read(&index).ok();
+
+    store::batch(|| {
+        store::backup(&key, b"-- batched\n").unwrap();
+        // read from the batch, the index is written when it ends
+        assert!(store::backup_hash(&key).unwrap().is_some());
+        assert_eq!(fs::read(&index).ok(), before);
+    })
+    .unwrap();
+    assert_ne!(fs::read(&index).ok(), before);
+    assert!(store::backup_hash(&key).unwrap().is_some());
+}
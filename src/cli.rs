@@ -0,0 +1,284 @@
+use std::env;
+use std::path::Path;
+
+use crate::assemble::Assemble;
+use crate::config::{Config, Installation};
+use crate::diff::{DiffKind, FileDiff};
+use crate::error::UranusError;
+use crate::logger::LEVEL_ARG;
+use crate::paths;
+use crate::snapshot::{self, FileChange, Snapshot};
+use crate::store;
+use crate::utils::{error_chain, read_from_file};
+use crate::vanilla;
+use crate::version;
+
+const USAGE: &str = "\
+Usage:
+    uranus                                  open the panel
+    uranus snapshot list [<Scripts>]        list the snapshots of the folder or all
+    uranus snapshot create <Scripts> [<label>]
+    uranus snapshot diff <id> [<id>]        compare with the folder or another snapshot
+    uranus snapshot restore <id> [<file>]   restore the file or the whole folder
+    uranus snapshot prune                   remove the snapshots out of the retention
+    uranus store verify                     check the backups and snapshots are intact
+    uranus store gc                         remove the contents no longer used
+    uranus vanilla [<installation>]         restore the backed up files of the installation
+                                            in use, or of its name or Scripts folder
+    uranus fingerprint [<installation>]     print the build of the game and the mods
+                                            verified on it
+
+Options:
+    --log-level <level>                     default, error, warn, info, debug or trace";
+
+/// Arguments without the log level, which is read by the logger.
+fn args() -> Vec<String> {
+    let prefix = format!("{}=", LEVEL_ARG);
+    let mut args = Vec::new();
+    let mut values = env::args().skip(1);
+    while let Some(arg) = values.next() {
+        if arg == LEVEL_ARG {
+            values.next();
+        } else if !arg.starts_with(&prefix) {
+            args.push(arg);
+        }
+    }
+    args
+}
+
+/// Write the output to the console the command was run from, the program is
+/// built without a console on Windows. The redirected output stays redirected.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // fails if run without a console, e.g. from the explorer, nothing to show then
+    // SAFETY: takes no pointer and only changes the console of this process
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+/// Run the command of the arguments, returns the exit code, or none to open
+/// the panel if there is no command.
+pub fn run() -> Option<i32> {
+    let args = args();
+    if args.is_empty() {
+        return None;
+    }
+    attach_console();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["snapshot", "list"] => snapshot_list(None),
+        ["snapshot", "list", hades_path] => snapshot_list(Some(*hades_path)),
+        ["snapshot", "create", hades_path] => snapshot_create(hades_path, "manual"),
+        ["snapshot", "create", hades_path, label] => snapshot_create(hades_path, label),
+        ["snapshot", "diff", id] => snapshot_diff(id, None),
+        ["snapshot", "diff", old, new] => snapshot_diff(old, Some(*new)),
+        ["snapshot", "restore", id] => snapshot_restore(id, None),
+        ["snapshot", "restore", id, file] => snapshot_restore(id, Some(*file)),
+        ["snapshot", "prune"] => snapshot_prune(),
+        ["store", "verify"] => store_verify(),
+        ["store", "gc"] => store_gc(),
+        ["vanilla"] => restore_vanilla(None),
+        ["vanilla", target] => restore_vanilla(Some(*target)),
+        ["fingerprint"] => fingerprint(None),
+        ["fingerprint", target] => fingerprint(Some(*target)),
+        ["help"] | ["--help"] | ["-h"] => {
+            println!("{}", USAGE);
+            return Some(0);
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return Some(2);
+        }
+    };
+    match result {
+        Ok(_) => Some(0),
+        Err(err) => {
+            eprintln!("error: {}", error_chain(&err));
+            eprintln!("{}", err.guidance());
+            Some(1)
+        }
+    }
+}
+
+fn print_snapshot(snapshot: &Snapshot) {
+    println!(
+        "{}  {}  {:<16} {} files  {}  {}",
+        snapshot.id,
+        snapshot.created,
+        snapshot.label,
+        snapshot.files.len(),
+        snapshot.fingerprint,
+        snapshot.hades_path
+    );
+}
+
+fn snapshot_list(hades_path: Option<&str>) -> Result<(), UranusError> {
+    let snapshots = snapshot::list(hades_path);
+    if snapshots.is_empty() {
+        println!("No snapshots");
+    }
+    for snapshot in &snapshots {
+        print_snapshot(snapshot);
+    }
+    Ok(())
+}
+
+fn snapshot_create(hades_path: &str, label: &str) -> Result<(), UranusError> {
+    let snapshot = snapshot::create(hades_path, label)?;
+    print_snapshot(&snapshot);
+    Ok(())
+}
+
+/// Print the changed lines of the file with their context.
+fn print_diff(file_diff: &FileDiff) {
+    println!("--- {}", file_diff.file_name);
+    for hunk in &file_diff.hunks {
+        println!("@@");
+        for line in hunk {
+            let sign = match line.kind {
+                DiffKind::Equal => ' ',
+                DiffKind::Insert => '+',
+                DiffKind::Delete => '-',
+            };
+            println!("{}{}", sign, line.text);
+        }
+    }
+}
+
+fn snapshot_diff(old: &str, new: Option<&str>) -> Result<(), UranusError> {
+    let old = snapshot::find(old)?;
+    let new = new.map(snapshot::find).transpose()?;
+    let changes = match &new {
+        Some(new) => snapshot::compare(&old, new),
+        None => snapshot::compare_current(&old)?,
+    };
+    if changes.is_empty() {
+        println!("No changes");
+    }
+    for (name, change) in changes {
+        match change {
+            FileChange::Added => println!("+++ {}", name),
+            FileChange::Removed => println!("--- {} removed", name),
+            FileChange::Modified => {
+                let after = match &new {
+                    Some(new) => new.read(&name)?,
+                    None => read_from_file(&old.current_path(&name))?,
+                };
+                print_diff(&FileDiff::new(&name, &old.read(&name)?, &after));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn snapshot_restore(id: &str, file: Option<&str>) -> Result<(), UranusError> {
+    let snapshot = snapshot::find(id)?;
+    let restored = snapshot::restore(&snapshot, file)?;
+    if restored.is_empty() {
+        println!("Nothing to restore");
+    }
+    for name in restored {
+        println!("restored {}", name);
+    }
+    Ok(())
+}
+
+fn snapshot_prune() -> Result<(), UranusError> {
+    for id in snapshot::prune(Config::load().snapshots)? {
+        println!("pruned {}", id);
+    }
+    Ok(())
+}
+
+fn store_verify() -> Result<(), UranusError> {
+    let errors = store::verify()?;
+    for err in &errors {
+        println!("{}", err);
+    }
+    match errors.into_iter().next() {
+        Some(err) => Err(err),
+        None => {
+            println!("The store is intact");
+            Ok(())
+        }
+    }
+}
+
+fn store_gc() -> Result<(), UranusError> {
+    let (count, bytes) = store::gc()?;
+    println!("removed {} contents, {} bytes", count, bytes);
+    Ok(())
+}
+
+/// Scripts folder of the installation of the name, or of the path, or of
+/// the installation in use if none.
+fn resolve_installation(config: &Config, target: Option<&str>) -> Result<String, UranusError> {
+    let installation = match target {
+        Some(target) => config
+            .installations
+            .iter()
+            .find(|installation| installation.name == target),
+        None => config.current_installation(),
+    };
+    if let Some(installation) = installation {
+        return Ok(installation.hades_path.clone());
+    }
+    let target = target.unwrap_or_default();
+    paths::scripts_dir(Path::new(target))
+        .map(|dir| dir.to_string_lossy().to_string())
+        .ok_or(UranusError::PathNotFound {
+            path: target.to_string(),
+        })
+}
+
+fn restore_vanilla(target: Option<&str>) -> Result<(), UranusError> {
+    let mut config = Config::load();
+    let hades_path = resolve_installation(&config, target)?;
+    let report = vanilla::restore(&hades_path)?;
+    if report.is_empty() {
+        println!("No backups of {}", hades_path);
+    }
+    for line in report.lines() {
+        println!("{}", line);
+    }
+
+    // no mod is applied any more
+    if let Some(installation) = config
+        .installations
+        .iter_mut()
+        .find(|installation| installation.hades_path == hades_path)
+    {
+        installation.enabled.clear();
+        if let Err(err) = config.save() {
+            log::error!("Cli: save config failed, {}", err);
+        }
+    }
+    Ok(())
+}
+
+fn fingerprint(target: Option<&str>) -> Result<(), UranusError> {
+    let config = Config::load();
+    let hades_path = resolve_installation(&config, target)?;
+    let mut assemble = Assemble::new();
+    assemble.set_installation(Installation::new(hades_path.clone()));
+    for (name, hash) in version::key_hashes(&hades_path, &assemble.backup_home)? {
+        println!("{:<18} {}", name, hash);
+    }
+    println!("fingerprint        {}", assemble.fingerprint);
+    for charism in assemble.charisms() {
+        let charism = charism.borrow();
+        let compatibility =
+            assemble.compatibility(&charism, config.verified_by_user(&charism.name));
+        println!("{:<24} {}", compatibility.label(), charism.name);
+    }
+    Ok(())
+}
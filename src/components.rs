@@ -1,41 +1,122 @@
+use crate::error::UranusError;
 use crate::utils::backup_from_origin;
 use crate::utils::{delete_regex, replace_regex, rollback_to_origin};
+use crate::utils::{read_from_file, read_origin, replace_content, scale_content, scale_regex};
 
 #[derive(Default)]
 pub struct Charism<'a> {
-    pub group: &'a str,
-    pub name: &'a str,
-    pub description: &'a str,
+    // borrowed for the built-in charisms, owned for the packages
+    pub group: Cow<'a, str>,
+    pub name: Cow<'a, str>,
+    pub description: Cow<'a, str>,
+    // fingerprints of the game the charism was verified against
+    pub verified: Vec<String>,
+    // value chosen in the panel, read by the pithos when applied
+    pub param: Option<Param>,
     strategy: Rc<RefCell<Strategy>>,
     backup_files: Rc<RefCell<Vec<String>>>,
-    backup_home: &'a str,
+    backup_home: String,
 }
 
 impl<'a> Charism<'a> {
-    pub fn new(group: &'a str, name: &'a str, description: &'a str) -> Self {
+    pub fn new(
+        group: impl Into<Cow<'a, str>>,
+        name: impl Into<Cow<'a, str>>,
+        description: impl Into<Cow<'a, str>>,
+    ) -> Self {
         Charism {
-            group,
-            name,
-            description,
-            strategy: Rc::new(RefCell::new(Strategy::new("./Eden"))),
+            group: group.into(),
+            name: name.into(),
+            description: description.into(),
+            verified: Vec::new(),
+            param: None,
+            // the backup home of the installation is set with the Scripts
+            // folder by `Assemble::set_installation`
+            strategy: Rc::new(RefCell::new(Strategy::new(""))),
             backup_files: Rc::new(RefCell::new(Vec::new())),
-            backup_home: "./Eden",
+            backup_home: String::new(),
         }
     }
 
-    pub fn set_backup_home(&mut self, backup_home: &'a str) {
-        self.backup_home = backup_home;
+    pub fn with_param(mut self, param: Param) -> Self {
+        self.param = Some(param);
+        self
+    }
+
+    pub fn set_backup_home(&mut self, backup_home: &str) {
+        self.backup_home = backup_home.to_string();
     }
 
     pub fn to_owned(&mut self) -> Self {
         Self {
-            group: self.group,
-            name: self.name,
-            description: self.description,
+            group: self.group.clone(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            verified: self.verified.clone(),
+            param: self.param.clone(),
             strategy: self.strategy.clone(),
             backup_files: self.backup_files.clone(),
-            backup_home: self.backup_home,
+            backup_home: self.backup_home.clone(),
+        }
+    }
+
+    /// Files modified by the charism.
+    pub fn files(&self) -> Vec<String> {
+        self.backup_files.borrow().clone()
+    }
+
+    /// Contents of each file as (file, before, after), the origin backup is
+    /// the before and the after is applied in memory without touching files.
+    pub fn preview(&self) -> Result<Vec<(String, String, String)>, UranusError> {
+        let binding = self.strategy.clone();
+        let strages: &Strategy = &binding.borrow();
+        let mut previews = Vec::new();
+        for file in self.files() {
+            let before = match read_origin(&self.backup_home, &file)? {
+                Some(origin) => origin,
+                None => read_from_file(&file)?,
+            };
+            let mut after = before.clone();
+            for strage in strages {
+                if strage.get_file_path() == file {
+                    after = strage.preview(&after)?;
+                }
+            }
+            previews.push((file, before, after));
         }
+        Ok(previews)
+    }
+
+    /// Whether the changes of the charism are found in its files: the lines it
+    /// adds to the origin files are all there, or the lines it only removes are gone.
+    /// `None` if its values change nothing, e.g. a multiplier of 1, so the files
+    /// can't tell.
+    pub fn is_applied(&self) -> Result<Option<bool>, UranusError> {
+        let mut changed = false;
+        for (file, before, after) in self.preview()? {
+            let current = read_from_file(&file)?;
+            let current: HashSet<&str> = current.lines().collect();
+            let before_lines: HashSet<&str> = before.lines().collect();
+            let after_lines: HashSet<&str> = after.lines().collect();
+
+            let added: Vec<&str> = after
+                .lines()
+                .filter(|line| !before_lines.contains(line))
+                .collect();
+            let lines_found = if added.is_empty() {
+                before
+                    .lines()
+                    .filter(|line| !after_lines.contains(line))
+                    .all(|line| !current.contains(line))
+            } else {
+                added.iter().all(|line| current.contains(line))
+            };
+            if !lines_found {
+                return Ok(Some(false));
+            }
+            changed |= before != after;
+        }
+        Ok(Some(true).filter(|_| changed))
     }
 
     pub fn show(&self) {
@@ -49,7 +130,7 @@ impl<'a> Charism<'a> {
     where
         'b: 'static + 'a,
     {
-        let backup_file_path = self.backup_home;
+        let backup_file_path = &self.backup_home;
         let binding = self.strategy.clone();
         let mut strages = binding.borrow_mut();
         strages.add(pitho.clone());
@@ -62,13 +143,16 @@ impl<'a> Charism<'a> {
         if !backup_files.contains(&file_path) {
             backup_files.push(file_path);
             match pitho.get_type() {
-                ApplyType::Replace | ApplyType::Delete => {
+                ApplyType::Replace | ApplyType::Delete | ApplyType::Scale | ApplyType::Fill => {
                     // backup origin file if not backup before
                     let file_path = pitho.get_file_path().to_string();
 
                     if backup_files.contains(&file_path) {
                         // rollback is the path checker, here will be ok
-                        backup_from_origin(&file_path, backup_file_path).unwrap();
+                        if let Err(err) = backup_from_origin(&file_path, backup_file_path) {
+                            // rollback will tell the backup is missing
+                            log::error!("[{}]({}) backup failed, {}", self.name, file_path, err);
+                        }
                     }
                 }
                 _ => {}
@@ -77,14 +161,30 @@ impl<'a> Charism<'a> {
         self.to_owned()
     }
 
-    pub fn apply(&'a self) -> Result<(), Box<dyn Error>> {
+    pub fn apply(&'a self) -> Result<(), UranusError> {
+        if let Err(err) = self.apply_pithos(true) {
+            self.rollback(true)?;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Apply the changes over the files as they are, without rolling them back
+    /// first, the files are left as they are if a change fails.
+    pub fn apply_changes(&self) -> Result<(), UranusError> {
+        self.apply_pithos(false)
+    }
+
+    fn apply_pithos(&self, rollback: bool) -> Result<(), UranusError> {
         // println!("<-{}->", self.group);
         let binding = self.strategy.clone();
         let strages: &Strategy = &binding.borrow();
         for strage in strages {
             if let ApplyType::RollBack = strage.get_type() {
                 // rollback()
-                self.rollback(false)?;
+                if rollback {
+                    self.rollback(false)?;
+                }
             } else {
                 // apply changes
                 match strage.do_apply() {
@@ -105,8 +205,7 @@ impl<'a> Charism<'a> {
                             strage.get_type(),
                             err
                         );
-                        log::error!("{}",message);
-                        self.rollback(true)?;
+                        log::error!("{}", message);
                         return Err(err);
                     }
                 }
@@ -116,8 +215,8 @@ impl<'a> Charism<'a> {
         Ok(())
     }
 
-    pub fn rollback(&self, is_over: bool) -> Result<(), Box<dyn Error>> {
-        let backup_file_path = self.backup_home;
+    pub fn rollback(&self, is_over: bool) -> Result<(), UranusError> {
+        let backup_file_path = &self.backup_home;
         let binding = self.backup_files.clone();
         let backup_files: &Vec<String> = &binding.borrow();
         for file in backup_files {
@@ -132,7 +231,7 @@ impl<'a> Charism<'a> {
                         "[{}]({}) apply {} failed, {}",
                         self.name, file, "RollBack", err
                     );
-                    log::error!("{}",message);
+                    log::error!("{}", message);
                     // self.rollback(true)?;
                     return Err(err);
                 }
@@ -146,42 +245,92 @@ impl<'a> Charism<'a> {
     }
 }
 
-use std::cell::RefCell;
-use std::error::Error;
-use std::fmt;
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
 use std::rc::Rc;
 
 #[derive(Debug)]
-pub struct CustomError {
-    pub message: String,
+pub enum ApplyType {
+    Backup,
+    RollBack,
+    Replace,
+    Delete,
+    Scale,
+    Fill,
 }
 
-impl CustomError {
-    pub fn new(message: &str) -> Box<dyn Error> {
-        Box::new(CustomError {
-            message: message.to_string(),
-        })
-    }
+/// A number of the charism chosen in the panel, e.g. a multiplier, shared
+/// with the pithos which read it when applied.
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub label: &'static str,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+    pub default: f64,
+    // names of the values from 0, a number if empty
+    pub choices: &'static [&'static str],
+    value: Rc<Cell<f64>>,
 }
 
-impl fmt::Display for CustomError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.message)
+impl Param {
+    pub fn new(label: &'static str, min: f64, max: f64, step: f64, default: f64) -> Self {
+        Param {
+            label,
+            min,
+            max,
+            step,
+            default,
+            choices: &[],
+            value: Rc::new(Cell::new(default)),
+        }
     }
-}
 
-impl Error for CustomError {}
+    /// A param choosing one of the names, e.g. a god.
+    pub fn choice(label: &'static str, choices: &'static [&'static str], default: usize) -> Self {
+        Param {
+            choices,
+            ..Param::new(
+                label,
+                0.0,
+                choices.len().saturating_sub(1) as f64,
+                1.0,
+                default as f64,
+            )
+        }
+    }
 
-#[derive(Debug)]
-pub enum ApplyType {
-    Backup,
-    RollBack,
-    Replace,
-    Delete,
+    pub fn get(&self) -> f64 {
+        self.value.get()
+    }
+
+    /// Set the value, kept within the range.
+    pub fn set(&self, value: f64) {
+        self.value.set(value.clamp(self.min, self.max));
+    }
+
+    /// The value as written to the game files, the name of a choice or the
+    /// number in the precision of the step.
+    pub fn text(&self) -> String {
+        let value = self.get();
+        match self.choices.get(value.round() as usize) {
+            Some(choice) => choice.to_string(),
+            None if self.step.fract() == 0.0 => format!("{:.0}", value),
+            None => format!("{:.1}", value),
+        }
+    }
+
+    /// The value shared with the pithos.
+    pub fn value(&self) -> Rc<Cell<f64>> {
+        self.value.clone()
+    }
 }
 
 pub trait Applyable {
-    fn do_apply(&self) -> Result<(), Box<dyn Error>>;
+    fn do_apply(&self) -> Result<(), UranusError>;
+    /// Apply to the content in memory instead of the file.
+    fn preview(&self, content: &str) -> Result<String, UranusError>;
     fn get_type(&self) -> ApplyType;
     fn get_file_path(&self) -> &str;
 }
@@ -198,9 +347,12 @@ impl Backup<'_> {
 }
 
 impl Applyable for Backup<'_> {
-    fn do_apply(&self) -> Result<(), Box<dyn Error>> {
+    fn do_apply(&self) -> Result<(), UranusError> {
         backup_from_origin(self.get_file_path(), self.to)
     }
+    fn preview(&self, content: &str) -> Result<String, UranusError> {
+        Ok(content.to_string())
+    }
     fn get_type(&self) -> ApplyType {
         ApplyType::Backup
     }
@@ -214,8 +366,11 @@ pub struct RollBack {
 }
 
 impl Applyable for RollBack {
-    fn do_apply(&self) -> Result<(), Box<dyn Error>> {
-        return Err(CustomError::new("needs to rollback before apply"));
+    fn do_apply(&self) -> Result<(), UranusError> {
+        return Err(UranusError::RollbackRequired);
+    }
+    fn preview(&self, content: &str) -> Result<String, UranusError> {
+        Ok(content.to_string())
     }
 
     fn get_type(&self) -> ApplyType {
@@ -232,25 +387,41 @@ impl RollBack {
     }
 }
 
-pub struct Replace<'a> {
+pub struct Replace {
     pub file_path: String,
-    pub from: &'a str,
-    pub to: &'a str,
+    pub from: String,
+    pub to: String,
+    // matches expected of `from`, any if none
+    pub count: Option<usize>,
 }
 
-impl Replace<'_> {
-    pub fn new<'a>(file_path: String, from: &'a str, to: &'a str) -> Rc<Replace<'a>> {
+impl Replace {
+    pub fn new(file_path: String, from: &str, to: &str) -> Rc<Replace> {
         Rc::new(Replace {
             file_path,
-            from,
-            to,
+            from: from.to_string(),
+            to: to.to_string(),
+            count: None,
+        })
+    }
+
+    /// Replace which fails unless `from` matches exactly count times.
+    pub fn exact(file_path: String, from: &str, to: &str, count: usize) -> Rc<Replace> {
+        Rc::new(Replace {
+            file_path,
+            from: from.to_string(),
+            to: to.to_string(),
+            count: Some(count),
         })
     }
 }
 
-impl Applyable for Replace<'_> {
-    fn do_apply(&self) -> Result<(), Box<dyn Error>> {
-        replace_regex(&self.file_path, &self.from, &self.to)
+impl Applyable for Replace {
+    fn do_apply(&self) -> Result<(), UranusError> {
+        replace_regex(&self.file_path, &self.from, &self.to, self.count)
+    }
+    fn preview(&self, content: &str) -> Result<String, UranusError> {
+        replace_content(&self.file_path, content, &self.from, &self.to, self.count)
     }
     fn get_type(&self) -> ApplyType {
         ApplyType::Replace
@@ -260,20 +431,38 @@ impl Applyable for Replace<'_> {
     }
 }
 
-pub struct Delete<'a> {
+pub struct Delete {
     pub file_path: String,
-    pub from: &'a str,
+    pub from: String,
+    // matches expected of `from`, any if none
+    pub count: Option<usize>,
 }
 
-impl Delete<'_> {
-    pub fn new<'a>(file_path: String, from: &'a str) -> Rc<Delete<'a>> {
-        Rc::new(Delete { file_path, from })
+impl Delete {
+    pub fn new(file_path: String, from: &str) -> Rc<Delete> {
+        Rc::new(Delete {
+            file_path,
+            from: from.to_string(),
+            count: None,
+        })
+    }
+
+    /// Delete which fails unless `from` matches exactly count times.
+    pub fn exact(file_path: String, from: &str, count: usize) -> Rc<Delete> {
+        Rc::new(Delete {
+            file_path,
+            from: from.to_string(),
+            count: Some(count),
+        })
     }
 }
 
-impl Applyable for Delete<'_> {
-    fn do_apply(&self) -> Result<(), Box<dyn Error>> {
-        delete_regex(&self.file_path, &self.from)
+impl Applyable for Delete {
+    fn do_apply(&self) -> Result<(), UranusError> {
+        delete_regex(&self.file_path, &self.from, self.count)
+    }
+    fn preview(&self, content: &str) -> Result<String, UranusError> {
+        replace_content(&self.file_path, content, &self.from, "", self.count)
     }
     fn get_type(&self) -> ApplyType {
         ApplyType::Delete
@@ -283,13 +472,135 @@ impl Applyable for Delete<'_> {
     }
 }
 
+/// Multiply the numbers captured as `value` by the pattern, by the value of
+/// the param when applied.
+pub struct Scale<'a> {
+    pub file_path: String,
+    pub from: &'a str,
+    pub factor: Rc<Cell<f64>>,
+    // matches expected of `from`, any if none
+    pub count: Option<usize>,
+}
+
+impl Scale<'_> {
+    pub fn new<'a>(file_path: String, from: &'a str, factor: Rc<Cell<f64>>) -> Rc<Scale<'a>> {
+        Rc::new(Scale {
+            file_path,
+            from,
+            factor,
+            count: None,
+        })
+    }
+
+    /// Scale which fails unless `from` matches exactly count times.
+    pub fn exact<'a>(
+        file_path: String,
+        from: &'a str,
+        factor: Rc<Cell<f64>>,
+        count: usize,
+    ) -> Rc<Scale<'a>> {
+        Rc::new(Scale {
+            file_path,
+            from,
+            factor,
+            count: Some(count),
+        })
+    }
+}
+
+impl Applyable for Scale<'_> {
+    fn do_apply(&self) -> Result<(), UranusError> {
+        scale_regex(&self.file_path, self.from, self.factor.get(), self.count)
+    }
+    fn preview(&self, content: &str) -> Result<String, UranusError> {
+        scale_content(
+            &self.file_path,
+            content,
+            self.from,
+            self.factor.get(),
+            self.count,
+        )
+    }
+    fn get_type(&self) -> ApplyType {
+        ApplyType::Scale
+    }
+    fn get_file_path(&self) -> &str {
+        &self.file_path
+    }
+}
+
+/// Replace the content according to the pattern with `to`, where `{value}`
+/// is the text of the param when applied.
+pub struct Fill<'a> {
+    pub file_path: String,
+    pub from: &'a str,
+    pub to: &'a str,
+    pub param: Param,
+    // matches expected of `from`, any if none
+    pub count: Option<usize>,
+}
+
+impl Fill<'_> {
+    pub fn new<'a>(file_path: String, from: &'a str, to: &'a str, param: Param) -> Rc<Fill<'a>> {
+        Rc::new(Fill {
+            file_path,
+            from,
+            to,
+            param,
+            count: None,
+        })
+    }
+
+    /// Fill which fails unless `from` matches exactly count times.
+    pub fn exact<'a>(
+        file_path: String,
+        from: &'a str,
+        to: &'a str,
+        param: Param,
+        count: usize,
+    ) -> Rc<Fill<'a>> {
+        Rc::new(Fill {
+            file_path,
+            from,
+            to,
+            param,
+            count: Some(count),
+        })
+    }
+
+    fn filled(&self) -> String {
+        self.to.replace("{value}", &self.param.text())
+    }
+}
+
+impl Applyable for Fill<'_> {
+    fn do_apply(&self) -> Result<(), UranusError> {
+        replace_regex(&self.file_path, self.from, &self.filled(), self.count)
+    }
+    fn preview(&self, content: &str) -> Result<String, UranusError> {
+        replace_content(
+            &self.file_path,
+            content,
+            self.from,
+            &self.filled(),
+            self.count,
+        )
+    }
+    fn get_type(&self) -> ApplyType {
+        ApplyType::Fill
+    }
+    fn get_file_path(&self) -> &str {
+        &self.file_path
+    }
+}
+
 #[derive(Default)]
 pub struct Strategy {
     pub pithos: Vec<Rc<dyn Applyable>>,
 }
 
 impl Strategy {
-    pub fn new(backup_home: &'static str) -> Strategy {
+    pub fn new(backup_home: &str) -> Strategy {
         Strategy {
             pithos: vec![RollBack::new(backup_home.to_string())],
         }
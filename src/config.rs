@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Language;
+use crate::logger::Verbosity;
+use crate::paths;
+use crate::snapshot::Retention;
+use crate::utils::{read_from_file, write_to_file};
+
+/// User choices which are kept between sessions.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub language: Language,
+    pub verbosity: Verbosity,
+    // name of the theme
+    pub theme: String,
+    pub window: WindowConfig,
+    // snapshots kept of each Scripts folder
+    pub snapshots: Retention,
+    // directory of the store and snapshots, the data directory if none
+    pub backup_root: Option<String>,
+    // name of the installation in use
+    pub installation: String,
+    pub installations: Vec<Installation>,
+    // fingerprints of the game each mod was verified on by the user
+    pub verified: BTreeMap<String, Vec<String>>,
+}
+
+/// A Scripts folder of the game with the mods applied to it and its presets.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Installation {
+    pub name: String,
+    pub hades_path: String,
+    // names of the mods applied by uranus
+    pub enabled: Vec<String>,
+    // names of the mods of each preset
+    pub presets: BTreeMap<String, Vec<String>>,
+    // values of the parameters of the mods, e.g. the multiplier of the Darkness
+    pub params: BTreeMap<String, f64>,
+}
+
+/// Size and position of the window when it was closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowConfig {
+    pub width: u32,
+    pub height: u32,
+    // placed by the system if none
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig {
+            width: 600,
+            height: 800,
+            x: None,
+            y: None,
+        }
+    }
+}
+
+impl Installation {
+    /// Installation of the Scripts folder, named after the folder of the game,
+    /// e.g. "Hades" of "Hades/Content/Scripts".
+    pub fn new(hades_path: String) -> Self {
+        let path = Path::new(&hades_path);
+        let name = path
+            .ancestors()
+            .filter_map(Path::file_name)
+            .map(|name| name.to_string_lossy().to_string())
+            .find(|name| {
+                !name.eq_ignore_ascii_case("Scripts") && !name.eq_ignore_ascii_case("Content")
+            })
+            .unwrap_or_else(|| hades_path.clone());
+        Installation {
+            name,
+            hades_path,
+            ..Installation::default()
+        }
+    }
+}
+
+impl Config {
+    /// The installation in use.
+    pub fn current_installation(&self) -> Option<&Installation> {
+        self.installations
+            .iter()
+            .find(|installation| installation.name == self.installation)
+    }
+
+    pub fn current_installation_mut(&mut self) -> Option<&mut Installation> {
+        let name = self.installation.clone();
+        self.installations
+            .iter_mut()
+            .find(|installation| installation.name == name)
+    }
+
+    /// Register the Scripts folder, returns the name of its installation, the
+    /// installation of the same folder is reused and the names are made unique.
+    pub fn add_installation(&mut self, hades_path: String) -> String {
+        if let Some(installation) = self
+            .installations
+            .iter()
+            .find(|installation| installation.hades_path == hades_path)
+        {
+            return installation.name.clone();
+        }
+        let mut installation = Installation::new(hades_path);
+        let base_name = installation.name.clone();
+        let mut suffix = 2;
+        while self
+            .installations
+            .iter()
+            .any(|value| value.name == installation.name)
+        {
+            installation.name = format!("{} ({})", base_name, suffix);
+            suffix += 1;
+        }
+        let name = installation.name.clone();
+        self.installations.push(installation);
+        name
+    }
+
+    /// Fingerprints of the game the mod of the name was verified on by the user.
+    pub fn verified_by_user(&self, name: &str) -> &[String] {
+        self.verified.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Record the mod as working on the fingerprint of the game.
+    pub fn add_verified(&mut self, name: &str, fingerprint: &str) {
+        let verified = self.verified.entry(name.to_string()).or_default();
+        if !verified.iter().any(|value| value == fingerprint) {
+            verified.push(fingerprint.to_string());
+        }
+    }
+
+    /// Forget the installation, its backups and snapshots are kept.
+    pub fn remove_installation(&mut self, name: &str) {
+        self.installations
+            .retain(|installation| installation.name != name);
+        if self.installation == name {
+            self.installation = self
+                .installations
+                .first()
+                .map_or(String::new(), |installation| installation.name.clone());
+        }
+    }
+
+    /// Load config from file, use default if missing or broken.
+    pub fn load() -> Self {
+        let config_file = paths::config_file();
+        if !config_file.exists() {
+            return Config::default();
+        }
+        let config_file = config_file.to_string_lossy();
+        let config = read_from_file(&config_file)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|content| toml::from_str(&content).map_err(Box::<dyn Error>::from));
+        match config {
+            Ok(config) => config,
+            Err(err) => {
+                log::warn!("load config `{}` failed, {}", config_file, err);
+                Config::default()
+            }
+        }
+    }
+
+    /// Save config to file.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let content = toml::to_string(self)?;
+        fs::create_dir_all(paths::config_dir())?;
+        write_to_file(&paths::config_file().to_string_lossy(), &content)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+use std::path::Path;
+
+use iced::alignment;
+use iced::theme;
+use iced::widget::{column, container, row, scrollable, text};
+use iced::{Color, Element, Font, Length, Theme};
+
+use crate::themes::UranusTheme;
+
+// lines of context around the changes
+const CONTEXT_LINES: usize = 3;
+// code font size
+const CODE_SIZE: f32 = 14.0;
+
+const LUA_KEYWORDS: &[&str] = &[
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "if", "in", "local",
+    "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+impl container::StyleSheet for DiffKind {
+    type Style = Theme;
+
+    fn appearance(&self, theme: &Theme) -> container::Appearance {
+        let palette = theme.extended_palette();
+
+        let background = match self {
+            DiffKind::Equal => None,
+            DiffKind::Insert => Some(palette.success.weak.color),
+            DiffKind::Delete => Some(palette.danger.weak.color),
+        };
+
+        container::Appearance {
+            background: background.map(|color| Color { a: 0.35, ..color }.into()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub old_number: Option<usize>,
+    pub new_number: Option<usize>,
+    pub text: String,
+}
+
+/// Changes of one file, only the changed lines and their context are kept.
+#[derive(Debug, Clone)]
+pub struct FileDiff {
+    pub file_name: String,
+    pub hunks: Vec<Vec<DiffLine>>,
+}
+
+impl FileDiff {
+    /// Compare the before and after content of the file.
+    pub fn new(file_path: &str, before: &str, after: &str) -> Self {
+        let file_name = match Path::new(file_path).file_name() {
+            Some(value) => value.to_string_lossy().to_string(),
+            None => file_path.to_string(),
+        };
+        let lines = diff_lines(before, after);
+        FileDiff {
+            file_name,
+            hunks: hunks(&lines, CONTEXT_LINES),
+        }
+    }
+}
+
+/// Line based diff of two texts by myers algorithm.
+pub fn diff_lines(before: &str, after: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let (n, m) = (old.len() as isize, new.len() as isize);
+    let max = (n + m) as usize;
+    let offset = max as isize + 1;
+
+    // furthest x of each diagonal k, and before every edit distance d the
+    // diagonals -d..=d it reaches from, to walk back
+    let mut v = vec![0isize; 2 * max + 3];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=max as isize {
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+        }
+    }
+
+    // walk back from the end to collect lines
+    let mut lines = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            // v[k + d] is the diagonal k
+            let k = x - y;
+            let prev_k = if k == -d || (k != d && v[(k - 1 + d) as usize] < v[(k + 1 + d) as usize])
+            {
+                k + 1
+            } else {
+                k - 1
+            };
+            let prev_x = v[(prev_k + d) as usize];
+            (prev_x, prev_x - prev_k)
+        };
+
+        while x > prev_x && y > prev_y {
+            lines.push(DiffLine {
+                kind: DiffKind::Equal,
+                old_number: Some(x as usize),
+                new_number: Some(y as usize),
+                text: old[x as usize - 1].to_string(),
+            });
+            x -= 1;
+            y -= 1;
+        }
+        if d > 0 {
+            if x == prev_x {
+                lines.push(DiffLine {
+                    kind: DiffKind::Insert,
+                    old_number: None,
+                    new_number: Some(y as usize),
+                    text: new[y as usize - 1].to_string(),
+                });
+            } else {
+                lines.push(DiffLine {
+                    kind: DiffKind::Delete,
+                    old_number: Some(x as usize),
+                    new_number: None,
+                    text: old[x as usize - 1].to_string(),
+                });
+            }
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    lines.reverse();
+    lines
+}
+
+/// Group the changed lines with their context lines.
+pub fn hunks(lines: &[DiffLine], context: usize) -> Vec<Vec<DiffLine>> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        if line.kind == DiffKind::Equal {
+            continue;
+        }
+        let start = index.saturating_sub(context);
+        let end = (index + context + 1).min(lines.len());
+        match ranges.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end)| lines[start..end].to_vec())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Plain,
+    Keyword,
+    String,
+    Number,
+    Comment,
+}
+
+/// Highlight colors of the code, taken from the theme so that they stay
+/// readable on its background.
+#[derive(Debug, Clone, Copy)]
+pub struct CodeColors {
+    pub keyword: Color,
+    pub string: Color,
+    pub number: Color,
+    pub comment: Color,
+}
+
+impl CodeColors {
+    pub fn new(theme: &UranusTheme) -> Self {
+        let (accent, highlight) = (theme.accent, theme.highlight);
+        CodeColors {
+            keyword: accent,
+            string: highlight,
+            // between the keywords and the strings
+            number: Color::from_rgb(
+                (accent.r + highlight.r) / 2.0,
+                (accent.g + highlight.g) / 2.0,
+                (accent.b + highlight.b) / 2.0,
+            ),
+            // the text faded
+            comment: Color {
+                a: 0.6,
+                ..theme.palette.text
+            },
+        }
+    }
+}
+
+impl Token {
+    fn color(&self, colors: &CodeColors) -> Option<Color> {
+        match self {
+            Token::Plain => None,
+            Token::Keyword => Some(colors.keyword),
+            Token::String => Some(colors.string),
+            Token::Number => Some(colors.number),
+            Token::Comment => Some(colors.comment),
+        }
+    }
+}
+
+/// Split a line of lua into highlighted pieces.
+fn highlight(line: &str) -> Vec<(Token, &str)> {
+    let mut pieces = Vec::new();
+    let bytes = line.as_bytes();
+    let mut start = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let (token, end) = if line[index..].starts_with("--") {
+            (Token::Comment, bytes.len())
+        } else if byte == b'"' || byte == b'\'' {
+            let mut end = index + 1;
+            while end < bytes.len() && bytes[end] != byte {
+                end += if bytes[end] == b'\\' { 2 } else { 1 };
+            }
+            (Token::String, (end + 1).min(bytes.len()))
+        } else if byte.is_ascii_digit() {
+            let mut end = index;
+            while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'.') {
+                end += 1;
+            }
+            (Token::Number, end)
+        } else if byte.is_ascii_alphabetic() || byte == b'_' {
+            let mut end = index;
+            while end < bytes.len() && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_') {
+                end += 1;
+            }
+            if LUA_KEYWORDS.contains(&&line[index..end]) {
+                (Token::Keyword, end)
+            } else {
+                // identifiers are plain, keep them with the pending piece
+                index = end;
+                continue;
+            }
+        } else {
+            index += line[index..].chars().next().map_or(1, char::len_utf8);
+            continue;
+        };
+
+        if start < index {
+            pieces.push((Token::Plain, &line[start..index]));
+        }
+        pieces.push((token, &line[index..end]));
+        index = end;
+        start = end;
+    }
+    if start < bytes.len() {
+        pieces.push((Token::Plain, &line[start..]));
+    }
+    pieces
+}
+
+/// Scrollable view of the file diffs.
+pub fn view<'a, Message: 'a>(
+    file_diffs: &'a [FileDiff],
+    colors: CodeColors,
+) -> Element<'a, Message> {
+    let mut files = column![].spacing(12);
+    for file_diff in file_diffs {
+        let mut hunks = column![text(&file_diff.file_name).size(CODE_SIZE + 4.0)].spacing(8);
+        for hunk in &file_diff.hunks {
+            let mut lines = column![];
+            for line in hunk {
+                lines = lines.push(line_view(line, &colors));
+            }
+            hunks = hunks.push(container(lines).style(theme::Container::Box));
+        }
+        files = files.push(hunks);
+    }
+    scrollable(files).height(Length::Fill).into()
+}
+
+/// A line with numbers, sign and highlighted code.
+fn line_view<'a, Message: 'a>(line: &'a DiffLine, colors: &CodeColors) -> Element<'a, Message> {
+    let number = |number: Option<usize>| {
+        text(number.map(|value| value.to_string()).unwrap_or_default())
+            .font(Font::MONOSPACE)
+            .size(CODE_SIZE)
+            .width(Length::Fixed(48.0))
+            .horizontal_alignment(alignment::Horizontal::Right)
+    };
+    let sign = match line.kind {
+        DiffKind::Equal => " ",
+        DiffKind::Insert => "+",
+        DiffKind::Delete => "-",
+    };
+
+    let mut code = row![
+        number(line.old_number),
+        number(line.new_number),
+        text(sign).font(Font::MONOSPACE).size(CODE_SIZE)
+    ]
+    .spacing(6);
+    for (token, piece) in highlight(&line.text) {
+        let piece = text(piece.replace('\t', "    "))
+            .font(Font::MONOSPACE)
+            .size(CODE_SIZE);
+        code = code.push(match token.color(colors) {
+            Some(color) => piece.style(color),
+            None => piece,
+        });
+    }
+
+    container(code)
+        .width(Length::Fill)
+        .style(theme::Container::Custom(Box::new(line.kind)))
+        .into()
+}
@@ -0,0 +1,148 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Errors of backing up, modifying and rolling back the game files.
+#[derive(Debug)]
+pub enum UranusError {
+    Io {
+        path: String,
+        source: io::Error,
+    },
+    PermissionDenied {
+        path: String,
+        source: io::Error,
+    },
+    PathNotFound {
+        path: String,
+    },
+    SameFile {
+        path: String,
+    },
+    InvalidPattern {
+        pattern: String,
+        source: regex::Error,
+    },
+    PatternNotFound {
+        file: String,
+        pattern: String,
+    },
+    UnexpectedMatchCount {
+        file: String,
+        pattern: String,
+        expected: usize,
+        found: usize,
+    },
+    BackupMissing {
+        path: String,
+    },
+    BackupCorrupt {
+        path: String,
+        reason: String,
+    },
+    RollbackRequired,
+    InvalidPackage {
+        path: String,
+        reason: String,
+    },
+    SnapshotNotFound {
+        id: String,
+    },
+}
+
+impl UranusError {
+    /// Wrap the io error of the path, permission errors are told apart.
+    pub fn io(path: impl AsRef<str>, source: io::Error) -> Self {
+        let path = path.as_ref().to_string();
+        match source.kind() {
+            io::ErrorKind::PermissionDenied => UranusError::PermissionDenied { path, source },
+            io::ErrorKind::NotFound => UranusError::PathNotFound { path },
+            _ => UranusError::Io { path, source },
+        }
+    }
+
+    /// What the user can do about the error, in english to be translated.
+    pub fn guidance(&self) -> &'static str {
+        match self {
+            UranusError::Io { .. } => "Check the file is not opened by the game or another tool.",
+            UranusError::PermissionDenied { .. } => {
+                "Run Uranus with the permission to write the game folder."
+            }
+            UranusError::PathNotFound { .. } => {
+                "Check the Scripts folder of the game is picked."
+            }
+            UranusError::SameFile { .. } => {
+                "Do not redirect the output of Uranus to the game files."
+            }
+            UranusError::InvalidPattern { .. } => "The mod is broken, please report it.",
+            UranusError::PatternNotFound { .. } | UranusError::UnexpectedMatchCount { .. } => {
+                "The game files may be another version or modified, verify them in Steam and try again."
+            }
+            UranusError::BackupMissing { .. } => {
+                "Pick the Scripts folder again to back up the origin files."
+            }
+            UranusError::BackupCorrupt { .. } => {
+                "The backup is damaged, verify the game files in Steam and pick the folder again."
+            }
+            UranusError::RollbackRequired => "Roll back the mod before applying it again.",
+            UranusError::InvalidPackage { .. } => {
+                "The mod package is broken, check it with its author."
+            }
+            UranusError::SnapshotNotFound { .. } => "List the snapshots and pick one of them.",
+        }
+    }
+}
+
+impl fmt::Display for UranusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UranusError::Io { path, .. } => write!(f, "io failed on `{}`", path),
+            UranusError::PermissionDenied { path, .. } => {
+                write!(f, "permission denied on `{}`", path)
+            }
+            UranusError::PathNotFound { path } => {
+                write!(f, "`{}` is not a directory or file", path)
+            }
+            UranusError::SameFile { path } => {
+                write!(f, "reading and writing to the same file `{}`", path)
+            }
+            UranusError::InvalidPattern { pattern, .. } => {
+                write!(f, "pattern `{}` is invalid", pattern)
+            }
+            UranusError::PatternNotFound { file, pattern } => {
+                write!(f, "pattern `{}` not found in `{}`", pattern, file)
+            }
+            UranusError::UnexpectedMatchCount {
+                file,
+                pattern,
+                expected,
+                found,
+            } => write!(
+                f,
+                "pattern `{}` expected {} matches in `{}`, found {}",
+                pattern, expected, file, found
+            ),
+            UranusError::BackupMissing { path } => write!(f, "backup `{}` is missing", path),
+            UranusError::BackupCorrupt { path, reason } => {
+                write!(f, "backup `{}` is corrupt, {}", path, reason)
+            }
+            UranusError::RollbackRequired => write!(f, "needs to rollback before apply"),
+            UranusError::InvalidPackage { path, reason } => {
+                write!(f, "mod package `{}` is invalid, {}", path, reason)
+            }
+            UranusError::SnapshotNotFound { id } => write!(f, "snapshot `{}` not found", id),
+        }
+    }
+}
+
+impl Error for UranusError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            UranusError::Io { source, .. } | UranusError::PermissionDenied { source, .. } => {
+                Some(source)
+            }
+            UranusError::InvalidPattern { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use iced::keyboard::{KeyCode, Modifiers};
+
+use crate::paths;
+use crate::utils::read_from_file;
+
+// names of the keys in "keymap.toml"
+const KEYS: &[(&str, KeyCode)] = &[
+    ("A", KeyCode::A),
+    ("B", KeyCode::B),
+    ("C", KeyCode::C),
+    ("D", KeyCode::D),
+    ("E", KeyCode::E),
+    ("F", KeyCode::F),
+    ("G", KeyCode::G),
+    ("H", KeyCode::H),
+    ("I", KeyCode::I),
+    ("J", KeyCode::J),
+    ("K", KeyCode::K),
+    ("L", KeyCode::L),
+    ("M", KeyCode::M),
+    ("N", KeyCode::N),
+    ("O", KeyCode::O),
+    ("P", KeyCode::P),
+    ("Q", KeyCode::Q),
+    ("R", KeyCode::R),
+    ("S", KeyCode::S),
+    ("T", KeyCode::T),
+    ("U", KeyCode::U),
+    ("V", KeyCode::V),
+    ("W", KeyCode::W),
+    ("X", KeyCode::X),
+    ("Y", KeyCode::Y),
+    ("Z", KeyCode::Z),
+    ("0", KeyCode::Key0),
+    ("1", KeyCode::Key1),
+    ("2", KeyCode::Key2),
+    ("3", KeyCode::Key3),
+    ("4", KeyCode::Key4),
+    ("5", KeyCode::Key5),
+    ("6", KeyCode::Key6),
+    ("7", KeyCode::Key7),
+    ("8", KeyCode::Key8),
+    ("9", KeyCode::Key9),
+    ("F1", KeyCode::F1),
+    ("F2", KeyCode::F2),
+    ("F3", KeyCode::F3),
+    ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5),
+    ("F6", KeyCode::F6),
+    ("F7", KeyCode::F7),
+    ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9),
+    ("F10", KeyCode::F10),
+    ("F11", KeyCode::F11),
+    ("F12", KeyCode::F12),
+    ("Space", KeyCode::Space),
+    ("Enter", KeyCode::Enter),
+    ("Escape", KeyCode::Escape),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+];
+
+/// Actions of the panel which can be bound to a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shortcut {
+    PickFolder,
+    Toggle,
+    Next,
+    Previous,
+    RollbackAll,
+    Undo,
+    Redo,
+    Preview,
+    Search,
+    Close,
+    CheatSheet,
+}
+
+impl Shortcut {
+    pub const ALL: [Shortcut; 11] = [
+        Shortcut::PickFolder,
+        Shortcut::Toggle,
+        Shortcut::Next,
+        Shortcut::Previous,
+        Shortcut::RollbackAll,
+        Shortcut::Undo,
+        Shortcut::Redo,
+        Shortcut::Preview,
+        Shortcut::Search,
+        Shortcut::Close,
+        Shortcut::CheatSheet,
+    ];
+
+    /// Name in "keymap.toml".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shortcut::PickFolder => "pick_folder",
+            Shortcut::Toggle => "toggle",
+            Shortcut::Next => "next",
+            Shortcut::Previous => "previous",
+            Shortcut::RollbackAll => "rollback_all",
+            Shortcut::Undo => "undo",
+            Shortcut::Redo => "redo",
+            Shortcut::Preview => "preview",
+            Shortcut::Search => "search",
+            Shortcut::Close => "close",
+            Shortcut::CheatSheet => "cheat_sheet",
+        }
+    }
+
+    /// Description in the cheat sheet, in english to be translated.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Shortcut::PickFolder => "Pick the Scripts folder",
+            Shortcut::Toggle => "Apply or roll back the selected mod",
+            Shortcut::Next => "Select the next mod",
+            Shortcut::Previous => "Select the previous mod",
+            Shortcut::RollbackAll => "Roll back all mods",
+            Shortcut::Undo => "Undo",
+            Shortcut::Redo => "Redo",
+            Shortcut::Preview => "Diff of the selected mod",
+            Shortcut::Search => "Search",
+            Shortcut::Close => "Close the pane",
+            Shortcut::CheatSheet => "Shortcuts",
+        }
+    }
+
+    fn default_binding(&self) -> KeyBinding {
+        let (key_code, command, shift) = match self {
+            Shortcut::PickFolder => (KeyCode::O, true, false),
+            Shortcut::Toggle => (KeyCode::Space, false, false),
+            Shortcut::Next => (KeyCode::Down, false, false),
+            Shortcut::Previous => (KeyCode::Up, false, false),
+            Shortcut::RollbackAll => (KeyCode::R, true, true),
+            Shortcut::Undo => (KeyCode::Z, true, false),
+            Shortcut::Redo => (KeyCode::Y, true, false),
+            Shortcut::Preview => (KeyCode::D, true, false),
+            Shortcut::Search => (KeyCode::F, true, false),
+            Shortcut::Close => (KeyCode::Escape, false, false),
+            Shortcut::CheatSheet => (KeyCode::F1, false, false),
+        };
+        KeyBinding {
+            key_code,
+            command,
+            shift,
+            alt: false,
+        }
+    }
+}
+
+/// A key with its modifiers, written as "Ctrl+Shift+Z".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub key_code: KeyCode,
+    // ctrl, or cmd on macOS
+    pub command: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let (mut command, mut shift, mut alt) = (false, false, false);
+        let mut key_code = None;
+        for part in value.split('+').map(str::trim) {
+            match part.to_lowercase().as_str() {
+                "ctrl" | "cmd" | "command" => command = true,
+                "shift" => shift = true,
+                "alt" => alt = true,
+                _ => match KEYS
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(part))
+                {
+                    Some((_, value)) if key_code.is_none() => key_code = Some(*value),
+                    _ => return Err(format!("key `{}` of `{}` is unknown", part, value)),
+                },
+            }
+        }
+        Ok(KeyBinding {
+            key_code: key_code.ok_or(format!("`{}` has no key", value))?,
+            command,
+            shift,
+            alt,
+        })
+    }
+
+    fn matches(&self, key_code: KeyCode, modifiers: Modifiers) -> bool {
+        self.key_code == key_code
+            && self.command == modifiers.command()
+            && self.shift == modifiers.shift()
+            && self.alt == modifiers.alt()
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.command {
+            write!(f, "Ctrl+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        match KEYS.iter().find(|(_, key_code)| *key_code == self.key_code) {
+            Some((name, _)) => write!(f, "{}", name),
+            None => write!(f, "{:?}", self.key_code),
+        }
+    }
+}
+
+/// Key bindings of the shortcuts.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Shortcut, KeyBinding)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap {
+            bindings: Shortcut::ALL
+                .iter()
+                .map(|shortcut| (*shortcut, shortcut.default_binding()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// The default bindings rebound by "keymap.toml" of the config directory,
+    /// e.g. `undo = "Ctrl+Z"`.
+    pub fn load() -> Self {
+        let mut keymap = Keymap::default();
+        let keymap_file = paths::config_dir().join("keymap.toml");
+        if !keymap_file.exists() {
+            return keymap;
+        }
+        let keymap_file = keymap_file.to_string_lossy();
+        let bindings: HashMap<String, String> = match read_from_file(&keymap_file)
+            .map_err(|err| err.to_string())
+            .and_then(|content| toml::from_str(&content).map_err(|err| err.to_string()))
+        {
+            Ok(bindings) => bindings,
+            Err(err) => {
+                log::warn!("load keymap `{}` failed, {}", keymap_file, err);
+                return keymap;
+            }
+        };
+
+        for (name, value) in bindings {
+            let shortcut = match Shortcut::ALL
+                .iter()
+                .find(|shortcut| shortcut.name() == name)
+            {
+                Some(shortcut) => *shortcut,
+                None => {
+                    log::warn!("keymap: shortcut `{}` is unknown", name);
+                    continue;
+                }
+            };
+            match KeyBinding::parse(&value) {
+                Ok(binding) => {
+                    for (value, old_binding) in keymap.bindings.iter_mut() {
+                        if *value == shortcut {
+                            *old_binding = binding;
+                        }
+                    }
+                }
+                Err(err) => log::warn!("keymap: `{}` is skipped, {}", name, err),
+            }
+        }
+        keymap
+    }
+
+    /// The shortcut bound to the key.
+    pub fn find(&self, key_code: KeyCode, modifiers: Modifiers) -> Option<Shortcut> {
+        self.bindings
+            .iter()
+            .find(|(_, binding)| binding.matches(key_code, modifiers))
+            .map(|(shortcut, _)| *shortcut)
+    }
+
+    /// The key bound to the shortcut.
+    pub fn binding(&self, shortcut: Shortcut) -> KeyBinding {
+        self.bindings
+            .iter()
+            .find(|(value, _)| *value == shortcut)
+            .map_or(shortcut.default_binding(), |(_, binding)| *binding)
+    }
+
+    pub fn bindings(&self) -> &[(Shortcut, KeyBinding)] {
+        &self.bindings
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Languages that the panel can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Chinese];
+
+    /// Translate an english text to the language, fall back to the text itself.
+    pub fn tr<'a>(&self, text: &'a str) -> &'a str {
+        match self {
+            Language::English => text,
+            Language::Chinese => chinese(text).unwrap_or(text),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::English => "English",
+            Language::Chinese => "简体中文",
+        }
+        .fmt(f)
+    }
+}
+
+/// Simplified Chinese string table, keyed by the english text.
+fn chinese(text: &str) -> Option<&'static str> {
+    let translated = match text {
+        // panel
+        "Path" => "路径",
+        "Pick Floder" => "选择文件夹",
+        "RollBack" => "回退",
+        "Language" => "语言",
+        "Enable All" => "全部启用",
+        "Disable All" => "全部禁用",
+        "All" => "全部",
+        "Applied" => "已应用",
+        "Available" => "未应用",
+        "No mods found" => "没有找到修改",
+        "Diff" => "对比",
+        "Close" => "关闭",
+        // groups
+        "Fishing" => "钓鱼",
+        "GifitTrait" => "信物",
+        "FreeStore" => "免费商店",
+        "HeroRarity" => "英雄级祝福",
+        "Economy" => "经济",
+        "BoonControl" => "祝福控制",
+        // toast
+        "Apply" => "应用",
+        "Tips" => "提示",
+        "success" => "成功",
+        "failed" => "失败",
+        "Pick Floder First" => "请先选择文件夹",
+        "Undo" => "撤销",
+        "Retry" => "重试",
+        "Open diff" => "查看对比",
+        // history
+        "History" => "历史",
+        "Copy" => "复制",
+        "No notifications" => "没有通知",
+        // logs
+        "Logs" => "日志",
+        "Search" => "搜索",
+        "Show log" => "查看日志",
+        "Verbosity" => "日志级别",
+        "No log file, log4rs.yaml has no file appender." => {
+            "没有日志文件，log4rs.yaml 中没有文件输出。"
+        }
+        // drag and drop
+        "Install" => "安装",
+        "takes effect after restart" => "重启后生效",
+        "No Scripts folder in" => "没有找到 Scripts 文件夹：",
+        "The mod package is broken, check it with its author." => {
+            "该模组包已损坏，请联系其作者检查。"
+        }
+        // watcher
+        "was changed by another program" => "已被其他程序修改",
+        "Re-apply" => "重新应用",
+        "Refresh" => "刷新",
+        // snapshots
+        "Snapshots" => "快照",
+        "Take snapshot" => "创建快照",
+        "Compare" => "对比",
+        "Prune" => "清理",
+        "Restore" => "恢复",
+        "Current" => "当前",
+        "files" => "个文件",
+        "files restored" => "个文件已恢复",
+        "snapshots pruned" => "个快照已清理",
+        "No snapshots" => "没有快照",
+        "No changes" => "没有改动",
+        "picked" => "选择文件夹时",
+        "manual" => "手动",
+        "before restore" => "恢复前",
+        // installations
+        "Installation" => "安装",
+        "Remove" => "移除",
+        "Presets" => "预设",
+        "Save preset" => "保存预设",
+        "Preset name" => "预设名称",
+        // vanilla
+        "Restore vanilla" => "恢复原版",
+        "Restore every backed up file and remove the files created by mods?" => {
+            "恢复所有备份的文件并删除模组创建的文件？"
+        }
+        "files removed" => "个文件已删除",
+        "No backups" => "没有备份",
+        "before vanilla" => "恢复原版前",
+        // versions
+        "untested on this version" => "未在此版本上测试",
+        "Mark verified" => "标记为已验证",
+        "Fingerprint" => "指纹",
+        // params
+        "Multiplier" => "倍数",
+        "God" => "神",
+        "Chance" => "概率",
+        "Choices" => "选项数",
+        "Levels" => "等级",
+        // gods
+        "Zeus" => "宙斯",
+        "Poseidon" => "波塞冬",
+        "Athena" => "雅典娜",
+        "Ares" => "阿瑞斯",
+        "Aphrodite" => "阿佛洛狄忒",
+        "Artemis" => "阿尔忒弥斯",
+        "Dionysus" => "狄俄尼索斯",
+        "Demeter" => "得墨忒耳",
+        // shortcuts
+        "Shortcuts" => "快捷键",
+        "Redo" => "重做",
+        "Pick the Scripts folder" => "选择 Scripts 文件夹",
+        "Apply or roll back the selected mod" => "应用或回退所选的修改",
+        "Select the next mod" => "选择下一个修改",
+        "Select the previous mod" => "选择上一个修改",
+        "Roll back all mods" => "回退所有修改",
+        "Diff of the selected mod" => "查看所选修改的对比",
+        "Close the pane" => "关闭面板",
+        "Primary" => "主要",
+        "Secondary" => "次要",
+        "Success" => "成功",
+        "Danger" => "错误",
+        // error guidance
+        "Check the file is not opened by the game or another tool." => {
+            "请确认文件没有被游戏或其他工具打开。"
+        }
+        "Run Uranus with the permission to write the game folder." => {
+            "请使用有权限写入游戏文件夹的身份运行 Uranus。"
+        }
+        "Check the Scripts folder of the game is picked." => "请确认选择的是游戏的 Scripts 文件夹。",
+        "Do not redirect the output of Uranus to the game files." => {
+            "请不要将 Uranus 的输出重定向到游戏文件。"
+        }
+        "The mod is broken, please report it." => "该模组已损坏，请反馈此问题。",
+        "The game files may be another version or modified, verify them in Steam and try again." => {
+            "游戏文件可能是其他版本或已被修改，请在 Steam 中验证后重试。"
+        }
+        "Pick the Scripts folder again to back up the origin files." => {
+            "请重新选择 Scripts 文件夹以备份原始文件。"
+        }
+        "The backup is damaged, verify the game files in Steam and pick the folder again." => {
+            "备份已损坏，请在 Steam 中验证游戏文件并重新选择文件夹。"
+        }
+        "Roll back the mod before applying it again." => "请先还原该模组再重新应用。",
+        "List the snapshots and pick one of them." => "请列出快照并选择其中一个。",
+        // mod names
+        "Always Fishing Point" => "总是钓鱼点",
+        "Catch Better Fish" => "能钓到更好的鱼",
+        "Easier To Pick Up" => "更容易起竿",
+        "GifitTrait Quick Upgrade" => "信物快速升级",
+        "Free Store Exchange" => "商场免费购买",
+        "Always Hero Raity Trait" => "总是英雄级祝福",
+        "Force Olympian" => "指定奥林匹斯神",
+        "Duo Boons" => "双神祝福",
+        "Legendary Boons" => "传说祝福",
+        "More Boon Choices" => "更多祝福选项",
+        "Pom Of Power Levels" => "力量石榴等级",
+        "Darkness Multiplier" => "黑暗倍数",
+        "Gems Multiplier" => "宝石倍数",
+        "Chthonic Keys Multiplier" => "冥界钥匙倍数",
+        "Nectar Multiplier" => "花蜜倍数",
+        "Diamonds Multiplier" => "钻石倍数",
+        "Ambrosia Multiplier" => "仙馔密酒倍数",
+        "Titan Blood Multiplier" => "泰坦之血倍数",
+        "Obols Multiplier" => "冥币倍数",
+        // mod descriptions
+        "Always eligible to fish." => "总是有资格钓鱼。",
+        "Increase biomefish weight." => "增加生物鱼的权重。",
+        "Increase fishing success." => "提高钓鱼的成功率。",
+        "Change chamber thresholds to one." => "信物升级的阈值改为1。",
+        "Modify the broker cost amount to negative." => "将商品花费改为负值。",
+        "Always hero raity trait." => "总是英雄级祝福。",
+        "The boon rooms are offered by the chosen god." => "祝福房间由所选的神提供。",
+        "Offer duo boons whenever their requirements are met." => "满足条件时总是提供双神祝福。",
+        "Offer legendary boons whenever their requirements are met." => {
+            "满足条件时总是提供传说祝福。"
+        }
+        "Raise the number of boons to choose from." => "增加可供选择的祝福数量。",
+        "Make Pom of Power grant more levels." => "力量石榴提升更多等级。",
+        "Multiply the Darkness of the room rewards." => "房间奖励的黑暗乘以倍数。",
+        "Multiply the Gems of the room rewards." => "房间奖励的宝石乘以倍数。",
+        "Multiply the Chthonic Keys of the room rewards." => "房间奖励的冥界钥匙乘以倍数。",
+        "Multiply the Nectar of the room rewards." => "房间奖励的花蜜乘以倍数。",
+        "Multiply the Diamonds of the room rewards." => "房间奖励的钻石乘以倍数。",
+        "Multiply the Ambrosia of the room rewards." => "房间奖励的仙馔密酒乘以倍数。",
+        "Multiply the Titan Blood of the room rewards." => "房间奖励的泰坦之血乘以倍数。",
+        "Multiply the Charon's Obols of the room rewards." => "房间奖励的卡戎冥币乘以倍数。",
+        _ => return None,
+    };
+    Some(translated)
+}
@@ -0,0 +1,337 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use log::LevelFilter;
+use log4rs::append::console::ConsoleAppender;
+use log4rs::append::rolling_file::policy::compound::roll::fixed_window::FixedWindowRoller;
+use log4rs::append::rolling_file::policy::compound::trigger::size::SizeTrigger;
+use log4rs::append::rolling_file::policy::compound::CompoundPolicy;
+use log4rs::append::rolling_file::RollingFileAppender;
+use log4rs::config::{Appender, Config, Deserializers, Logger, RawConfig, Root};
+use log4rs::encode::pattern::PatternEncoder;
+use log4rs::Handle;
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+// level given by the command line or the environment
+pub const LEVEL_ARG: &str = "--log-level";
+const LEVEL_ENV: &str = "URANUS_LOG";
+// parsed back by `logs::LogTail`, keep them in step
+const PATTERN: &str = "[{d(%Y-%m-%d %H:%M:%S.%s):23.23}][{f}:{L} @{M}][{P}][{l}] {m}{n}";
+// size of the log file before rolling and the rolled files kept
+const FILE_LIMIT: u64 = 1024 * 1024;
+const FILE_COUNT: u32 = 3;
+
+/// Level of the logs written by uranus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Verbosity {
+    // info for the panel, debug for the charisms
+    #[default]
+    Default,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Verbosity {
+    pub const ALL: [Verbosity; 6] = [
+        Verbosity::Default,
+        Verbosity::Error,
+        Verbosity::Warn,
+        Verbosity::Info,
+        Verbosity::Debug,
+        Verbosity::Trace,
+    ];
+
+    /// Parse the value of `--log-level` or `URANUS_LOG`, case insensitive.
+    pub fn parse(value: &str) -> Option<Self> {
+        Verbosity::ALL
+            .into_iter()
+            .find(|verbosity| verbosity.to_string().eq_ignore_ascii_case(value.trim()))
+    }
+
+    fn filter(&self) -> Option<LevelFilter> {
+        match self {
+            Verbosity::Default => None,
+            Verbosity::Error => Some(LevelFilter::Error),
+            Verbosity::Warn => Some(LevelFilter::Warn),
+            Verbosity::Info => Some(LevelFilter::Info),
+            Verbosity::Debug => Some(LevelFilter::Debug),
+            Verbosity::Trace => Some(LevelFilter::Trace),
+        }
+    }
+}
+
+impl fmt::Display for Verbosity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verbosity::Default => "Default",
+            Verbosity::Error => "Error",
+            Verbosity::Warn => "Warn",
+            Verbosity::Info => "Info",
+            Verbosity::Debug => "Debug",
+            Verbosity::Trace => "Trace",
+        }
+        .fmt(f)
+    }
+}
+
+struct State {
+    handle: Handle,
+    // "log4rs.yaml" if it was loaded
+    file: Option<PathBuf>,
+    // file written by the configuration, if any
+    log_file: Option<PathBuf>,
+}
+
+static STATE: OnceLock<State> = OnceLock::new();
+// the level was given by the command line or the environment
+static OVERRIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// File written by the built-in configuration.
+pub fn log_file() -> PathBuf {
+    paths::log_dir().join("uranus.log")
+}
+
+/// File written by the running configuration, the one of the first "file"
+/// or "rolling_file" appender for "log4rs.yaml", `None` if it has none.
+pub fn active_log_file() -> Option<PathBuf> {
+    match STATE.get() {
+        Some(state) => state.log_file.clone(),
+        None => Some(log_file()),
+    }
+}
+
+/// Init the logger by "log4rs.yaml" of the config directory if it exists,
+/// else by the built-in configuration. The level of `--log-level` or
+/// `URANUS_LOG` wins.
+pub fn init() {
+    let verbosity = arg_verbosity().or_else(env_verbosity);
+    OVERRIDDEN.store(verbosity.is_some(), Ordering::Relaxed);
+    let verbosity = verbosity.unwrap_or_default();
+
+    let mut warnings = Vec::new();
+    let mut file = Some(paths::log_config_file()).filter(|path| path.exists());
+    let config = match load(file.as_deref(), verbosity, &mut warnings) {
+        Ok(config) => config,
+        Err(err) => {
+            warnings.push(format!(
+                "load `{}` failed, {}, use the built-in config",
+                paths::log_config_file().display(),
+                err
+            ));
+            file = None;
+            builtin(verbosity, &mut warnings)
+        }
+    };
+
+    let log_file = match &file {
+        Some(path) => yaml_log_file(path),
+        None => Some(log_file()),
+    };
+    match log4rs::init_config(config) {
+        Ok(handle) => {
+            let _ = STATE.set(State {
+                handle,
+                file,
+                log_file,
+            });
+        }
+        // without console there is nowhere to tell
+        Err(_) => return,
+    }
+    for warning in warnings {
+        log::warn!("Logger: {}.", warning);
+    }
+}
+
+/// The level was given by the command line or the environment.
+pub fn is_overridden() -> bool {
+    OVERRIDDEN.load(Ordering::Relaxed)
+}
+
+/// Change the level of the running logger.
+pub fn set_verbosity(verbosity: Verbosity) {
+    let state = match STATE.get() {
+        Some(state) => state,
+        None => return,
+    };
+    let mut warnings = Vec::new();
+    match load(state.file.as_deref(), verbosity, &mut warnings) {
+        Ok(config) => {
+            state.handle.set_config(config);
+            log::info!("Logger: verbosity changed to {}.", verbosity);
+        }
+        Err(err) => log::error!("Logger: reload log4rs.yaml failed, {}", err),
+    }
+    for warning in warnings {
+        log::warn!("Logger: {}.", warning);
+    }
+}
+
+fn arg_verbosity() -> Option<Verbosity> {
+    let prefix = format!("{}=", LEVEL_ARG);
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = if arg == LEVEL_ARG {
+            args.next()
+        } else {
+            arg.strip_prefix(&prefix).map(str::to_string)
+        };
+        if let Some(value) = value {
+            return Verbosity::parse(&value);
+        }
+    }
+    None
+}
+
+fn env_verbosity() -> Option<Verbosity> {
+    env::var(LEVEL_ENV)
+        .ok()
+        .and_then(|value| Verbosity::parse(&value))
+}
+
+/// Configuration of the file if given, the verbosity sets the level of its
+/// root and of each of its loggers, which would else keep their own.
+fn load(
+    file: Option<&Path>,
+    verbosity: Verbosity,
+    warnings: &mut Vec<String>,
+) -> Result<Config, String> {
+    let path = match file {
+        Some(path) => path,
+        None => return Ok(builtin(verbosity, warnings)),
+    };
+    let content = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    let raw: RawConfig = serde_yaml::from_str(&content).map_err(|err| err.to_string())?;
+    if raw.refresh_rate().is_some() {
+        warnings.push(
+            "refresh_rate of log4rs.yaml is ignored, choose the verbosity again to reload it"
+                .to_string(),
+        );
+    }
+
+    let (appenders, errors) = raw.appenders_lossy(&Deserializers::default());
+    if !errors.is_empty() {
+        warnings.push(errors.to_string());
+    }
+    let mut root = raw.root();
+    let mut loggers = raw.loggers();
+    if let Some(level) = verbosity.filter() {
+        root.set_level(level);
+        loggers = loggers
+            .into_iter()
+            .map(|logger| {
+                Logger::builder()
+                    .appenders(logger.appenders().iter().cloned())
+                    .additive(logger.additive())
+                    .build(logger.name(), level)
+            })
+            .collect();
+    }
+    Config::builder()
+        .appenders(appenders)
+        .loggers(loggers)
+        .build(root)
+        .map_err(|err| err.to_string())
+}
+
+/// Path of the first "file" or "rolling_file" appender used by the root, then
+/// by the other loggers. As log4rs does, `$ENV{NAME}` is replaced by the
+/// variable and relative paths start from the working directory.
+fn yaml_log_file(path: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(path).ok()?;
+    let yaml: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
+    let appenders_of = |logger: &serde_yaml::Value| {
+        logger
+            .get("appenders")
+            .and_then(serde_yaml::Value::as_sequence)
+            .into_iter()
+            .flatten()
+            .filter_map(serde_yaml::Value::as_str)
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    let mut names = yaml.get("root").map(appenders_of).unwrap_or_default();
+    if let Some(loggers) = yaml.get("loggers").and_then(serde_yaml::Value::as_mapping) {
+        for (_, logger) in loggers.iter() {
+            names.extend(appenders_of(logger));
+        }
+    }
+
+    let appenders = yaml.get("appenders")?;
+    let file = names.iter().find_map(|name| {
+        let appender = appenders.get(name.as_str())?;
+        let kind = appender.get("kind")?.as_str()?;
+        if kind != "file" && kind != "rolling_file" {
+            return None;
+        }
+        appender.get("path")?.as_str()
+    })?;
+    let file = Regex::new(r"\$ENV\{([^}]*)\}")
+        .unwrap()
+        .replace_all(file, |captures: &Captures| {
+            env::var(&captures[1]).unwrap_or_else(|_| captures[0].to_string())
+        });
+    let file = PathBuf::from(file.as_ref());
+    if file.is_relative() {
+        env::current_dir().ok().map(|dir| dir.join(file))
+    } else {
+        Some(file)
+    }
+}
+
+/// Console and rolling file, the file is skipped if it can't be created.
+fn builtin(verbosity: Verbosity, warnings: &mut Vec<String>) -> Config {
+    let console = ConsoleAppender::builder()
+        .encoder(Box::new(PatternEncoder::new(PATTERN)))
+        .build();
+    let mut builder =
+        Config::builder().appender(Appender::builder().build("console", Box::new(console)));
+    let mut appenders = vec!["console"];
+
+    match rolling_file() {
+        Ok(file) => {
+            builder = builder.appender(Appender::builder().build("file", Box::new(file)));
+            appenders.push("file");
+        }
+        Err(err) => warnings.push(format!(
+            "create log file `{}` failed, {}",
+            log_file().display(),
+            err
+        )),
+    }
+
+    builder = match verbosity.filter() {
+        Some(level) => builder.logger(Logger::builder().build("uranus", level)),
+        None => builder
+            .logger(Logger::builder().build("uranus", LevelFilter::Info))
+            .logger(Logger::builder().build("uranus::components", LevelFilter::Debug)),
+    };
+    // other crates only tell errors
+    builder
+        .build(
+            Root::builder()
+                .appenders(appenders)
+                .build(LevelFilter::Error),
+        )
+        .unwrap()
+}
+
+fn rolling_file() -> Result<RollingFileAppender, Box<dyn std::error::Error>> {
+    let roll_pattern = paths::log_dir().join("uranus.{}.log");
+    let roller = FixedWindowRoller::builder().build(&roll_pattern.to_string_lossy(), FILE_COUNT)?;
+    let policy = CompoundPolicy::new(Box::new(SizeTrigger::new(FILE_LIMIT)), Box::new(roller));
+    let file = RollingFileAppender::builder()
+        .encoder(Box::new(PatternEncoder::new(PATTERN)))
+        .build(log_file(), Box::new(policy))?;
+    Ok(file)
+}
@@ -0,0 +1,191 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use iced::theme;
+use iced::widget::{column, container, row, scrollable, text};
+use iced::{Element, Length};
+use log::Level;
+use regex::Regex;
+
+use crate::logger;
+use crate::panel::YY_FONT;
+use crate::toast::Status;
+
+// bytes read from the end of the file when opened
+const TAIL_BYTES: u64 = 256 * 1024;
+// entries kept in memory
+const MAX_ENTRIES: usize = 5000;
+// log text size
+const LOG_SIZE: f32 = 13.0;
+
+/// An entry of the log file, following the pattern of `logger`:
+/// `[{d}][{f}:{L} @{M}][{P}][{l}] {m}{n}`
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: String,
+    pub location: String,
+    pub level: Option<Level>,
+    pub message: String,
+    // logged since the panel started
+    pub session: bool,
+}
+
+impl LogEntry {
+    /// Logged by `Charism::apply` or `Charism::rollback` in this session.
+    pub fn is_charism(&self) -> bool {
+        self.session && self.location.contains("@uranus::components")
+    }
+
+    fn matches(&self, keyword: &str) -> bool {
+        [&self.time, &self.location, &self.message]
+            .iter()
+            .any(|field| field.to_lowercase().contains(keyword))
+    }
+}
+
+/// Reads the entries appended to the log file.
+pub struct LogTail {
+    // `None` if the logger writes no file
+    path: Option<PathBuf>,
+    // bytes of the file already read
+    offset: u64,
+    // file length when the panel started
+    session_offset: u64,
+    pattern: Regex,
+    pub entries: Vec<LogEntry>,
+}
+
+impl LogTail {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        let session_offset = path
+            .as_ref()
+            .and_then(|path| fs::metadata(path).ok())
+            .map_or(0, |metadata| metadata.len());
+        LogTail {
+            path,
+            offset: session_offset.saturating_sub(TAIL_BYTES),
+            session_offset,
+            pattern: Regex::new(r"^\[([^\]]*)\]\[([^\]]*)\]\[[^\]]*\]\[([A-Z]+)\] ?(.*)$").unwrap(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// There is a log file to read.
+    pub fn is_available(&self) -> bool {
+        self.path.is_some()
+    }
+
+    /// Read the new lines of the log file.
+    pub fn poll(&mut self) -> Result<(), Box<dyn Error>> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let mut file = File::open(path)?;
+        let length = file.metadata()?.len();
+        // the file was truncated or rolled
+        if length < self.offset {
+            self.offset = 0;
+            self.session_offset = 0;
+            self.entries.clear();
+        }
+        let start = self.offset;
+        file.seek(SeekFrom::Start(start))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        // keep the incomplete last line for the next poll
+        let complete = match bytes.iter().rposition(|byte| *byte == b'\n') {
+            Some(position) => position + 1,
+            None => return Ok(()),
+        };
+        self.offset += complete as u64;
+
+        let content = String::from_utf8_lossy(&bytes[..complete]);
+        let mut line_offset = start;
+        for line in content.split_inclusive('\n') {
+            let session = line_offset >= self.session_offset;
+            line_offset += line.len() as u64;
+            let line = line.trim_end_matches(['\r', '\n']);
+            match self.pattern.captures(line) {
+                Some(captures) => self.entries.push(LogEntry {
+                    time: captures[1].to_string(),
+                    location: captures[2].to_string(),
+                    level: Level::from_str(&captures[3]).ok(),
+                    message: captures[4].to_string(),
+                    session,
+                }),
+                // continuation of a multi-line message
+                None => match self.entries.last_mut() {
+                    Some(entry) => {
+                        entry.message.push('\n');
+                        entry.message.push_str(line);
+                    }
+                    // skip the partial line of the tail start
+                    None if start > 0 => {}
+                    None => self.entries.push(LogEntry {
+                        time: String::new(),
+                        location: String::new(),
+                        level: None,
+                        message: line.to_string(),
+                        session,
+                    }),
+                },
+            }
+        }
+
+        if self.entries.len() > MAX_ENTRIES {
+            let overflow = self.entries.len() - MAX_ENTRIES;
+            self.entries.drain(..overflow);
+        }
+        Ok(())
+    }
+
+    /// Entries at or above the level which contain the keyword.
+    pub fn filtered(&self, level: Level, keyword: &str) -> Vec<&LogEntry> {
+        let keyword = keyword.trim().to_lowercase();
+        self.entries
+            .iter()
+            .filter(|entry| entry.level.map_or(true, |value| value <= level))
+            .filter(|entry| keyword.is_empty() || entry.matches(&keyword))
+            .collect()
+    }
+}
+
+impl Default for LogTail {
+    fn default() -> Self {
+        LogTail::new(logger::active_log_file())
+    }
+}
+
+/// Scrollable view of the entries, the charism entries of this session are highlighted.
+pub fn view<'a, Message: 'a>(entries: Vec<&'a LogEntry>) -> Element<'a, Message> {
+    let mut lines = column![].spacing(2);
+    for entry in entries {
+        let level = entry
+            .level
+            .map(|level| level.to_string())
+            .unwrap_or_default();
+        let line = row![
+            text(&entry.time).font(YY_FONT).size(LOG_SIZE),
+            text(level).font(YY_FONT).size(LOG_SIZE),
+            text(&entry.message).font(YY_FONT).size(LOG_SIZE)
+        ]
+        .spacing(8);
+
+        let line = container(line).width(Length::Fill).padding(2);
+        lines = lines.push(if entry.is_charism() {
+            let status = match entry.level {
+                Some(Level::Error) | Some(Level::Warn) => Status::Danger,
+                _ => Status::Success,
+            };
+            line.style(theme::Container::Custom(Box::new(status)))
+        } else {
+            line
+        });
+    }
+    scrollable(lines).height(Length::Fill).into()
+}
@@ -2,10 +2,26 @@
 #![windows_subsystem = "windows"]
 
 pub mod assemble;
+pub mod cli;
 pub mod components;
+pub mod config;
+pub mod diff;
+pub mod error;
+pub mod keymap;
+pub mod locale;
+pub mod logger;
+pub mod logs;
+pub mod package;
 pub mod panel;
+pub mod paths;
+pub mod snapshot;
+pub mod store;
+pub mod themes;
 pub mod toast;
 pub mod utils;
+pub mod vanilla;
+pub mod version;
+pub mod watcher;
 
 extern crate log;
 extern crate log4rs;
@@ -13,8 +29,14 @@ extern crate log4rs;
 use panel::pannel_main;
 
 fn main() {
-    // init log4rs by file "log4rs.yaml"
-    log4rs::init_file("log4rs.yaml", Default::default()).unwrap();
+    // init log4rs by file "log4rs.yaml" or the built-in config
+    logger::init();
+    // move the backups and config of older versions
+    paths::migrate();
+    // run the command of the arguments without the panel
+    if let Some(code) = cli::run() {
+        std::process::exit(code);
+    }
     //  launch panel
     pannel_main().unwrap();
 }
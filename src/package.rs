@@ -0,0 +1,273 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::error::UranusError;
+use crate::paths;
+use crate::utils::read_from_file;
+
+// group of the packages which don't tell one
+const DEFAULT_GROUP: &str = "Packages";
+
+/// A mod written as a toml file, installed into the "mods" directory:
+///
+/// ```toml
+/// group = "Store"
+/// name = "Cheaper Store"
+/// description = "Halve the cost of the store."
+///
+/// [[ops]]
+/// kind = "replace"
+/// file = "StoreData.lua"
+/// from = 'CostAmount = (\d+)'
+/// to = 'CostAmount = 1'
+/// # optional, matches expected of `from`
+/// count = 12
+/// ```
+///
+/// A package may tell the fingerprints of the game it was verified against,
+/// and other ops for the builds where the patterns differ:
+///
+/// ```toml
+/// verified = ["1a2b3c4d5e6f7a8b"]
+///
+/// [[variants]]
+/// verified = ["8b7a6f5e4d3c2b1a"]
+/// [[variants.ops]]
+/// kind = "delete"
+/// file = "StoreData.lua"
+/// from = 'Cost = \d+,'
+/// ```
+#[derive(Debug, Clone)]
+pub struct ModPackage {
+    pub group: String,
+    pub name: String,
+    pub description: String,
+    pub ops: Vec<PackageOp>,
+    // fingerprints of the game the ops were verified against
+    pub verified: Vec<String>,
+    pub variants: Vec<PackageVariant>,
+}
+
+/// Ops of a package for other builds of the game.
+#[derive(Debug, Clone)]
+pub struct PackageVariant {
+    pub verified: Vec<String>,
+    pub ops: Vec<PackageOp>,
+}
+
+impl ModPackage {
+    /// Ops for the fingerprint of the game, those of the variant verified
+    /// against it, or the default ones.
+    pub fn ops_for(&self, fingerprint: &str) -> &[PackageOp] {
+        self.variants
+            .iter()
+            .find(|variant| variant.verified.iter().any(|value| value == fingerprint))
+            .map_or(&self.ops, |variant| &variant.ops)
+    }
+
+    /// Fingerprints the package was verified against, of all its variants.
+    pub fn all_verified(&self) -> Vec<String> {
+        let mut verified = self.verified.clone();
+        for variant in &self.variants {
+            verified.extend(variant.verified.iter().cloned());
+        }
+        verified
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PackageOp {
+    Replace {
+        file: String,
+        from: String,
+        to: String,
+        count: Option<usize>,
+    },
+    Delete {
+        file: String,
+        from: String,
+        count: Option<usize>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageFile {
+    group: Option<String>,
+    name: String,
+    #[serde(default)]
+    description: String,
+    ops: Vec<OpEntry>,
+    #[serde(default)]
+    verified: Vec<String>,
+    #[serde(default)]
+    variants: Vec<VariantEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VariantEntry {
+    verified: Vec<String>,
+    ops: Vec<OpEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpEntry {
+    kind: String,
+    file: String,
+    from: String,
+    to: Option<String>,
+    count: Option<usize>,
+}
+
+// packages loaded in this session, the mods directory is read once
+static PACKAGES: Mutex<Option<Vec<ModPackage>>> = Mutex::new(None);
+
+/// Directory of the installed packages.
+pub fn mods_dir() -> PathBuf {
+    paths::data_dir().join("mods")
+}
+
+/// Whether the file looks like a mod package.
+pub fn is_package(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .map_or(false, |extension| extension.eq_ignore_ascii_case("toml"))
+}
+
+/// Read and check a package file.
+fn parse(path: &Path) -> Result<PackageFile, UranusError> {
+    let path_name = path.to_string_lossy().to_string();
+    let invalid = |reason: String| UranusError::InvalidPackage {
+        path: path_name.clone(),
+        reason,
+    };
+
+    let content = read_from_file(&path_name)?;
+    let package: PackageFile = toml::from_str(&content).map_err(|err| invalid(err.to_string()))?;
+    check_ops(&package.ops, &invalid)?;
+    for variant in &package.variants {
+        if variant.verified.is_empty() {
+            return Err(invalid(
+                "variant verified against no fingerprint".to_string(),
+            ));
+        }
+        check_ops(&variant.ops, &invalid)?;
+    }
+    Ok(package)
+}
+
+fn check_ops(ops: &[OpEntry], invalid: &impl Fn(String) -> UranusError) -> Result<(), UranusError> {
+    if ops.is_empty() {
+        return Err(invalid("no ops".to_string()));
+    }
+    for op in ops {
+        // only the files of the Scripts folder
+        if Path::new(&op.file).file_name() != Some(OsStr::new(&op.file)) {
+            return Err(invalid(format!("file `{}` is not a file name", op.file)));
+        }
+        match (op.kind.as_str(), &op.to) {
+            ("replace", Some(_)) | ("delete", None) => {}
+            ("replace", None) => return Err(invalid("replace needs `to`".to_string())),
+            ("delete", Some(_)) => return Err(invalid("delete has no `to`".to_string())),
+            (kind, _) => return Err(invalid(format!("kind `{}` is unknown", kind))),
+        }
+        Regex::new(&op.from).map_err(|err| UranusError::InvalidPattern {
+            pattern: op.from.clone(),
+            source: err,
+        })?;
+    }
+    Ok(())
+}
+
+fn load_ops(ops: Vec<OpEntry>) -> Vec<PackageOp> {
+    ops.into_iter()
+        .map(|op| match op.to {
+            Some(to) => PackageOp::Replace {
+                file: op.file,
+                from: op.from,
+                to,
+                count: op.count,
+            },
+            None => PackageOp::Delete {
+                file: op.file,
+                from: op.from,
+                count: op.count,
+            },
+        })
+        .collect()
+}
+
+fn load(path: &Path) -> Result<ModPackage, UranusError> {
+    let package = parse(path)?;
+    let variants = package
+        .variants
+        .into_iter()
+        .map(|variant| PackageVariant {
+            verified: variant.verified,
+            ops: load_ops(variant.ops),
+        })
+        .collect();
+    Ok(ModPackage {
+        group: package.group.unwrap_or_else(|| DEFAULT_GROUP.to_string()),
+        name: package.name,
+        description: package.description,
+        ops: load_ops(package.ops),
+        verified: package.verified,
+        variants,
+    })
+}
+
+/// Packages of the mods directory, read once per session.
+fn with_packages<R>(f: impl FnOnce(&mut Vec<ModPackage>) -> R) -> R {
+    let mut packages = PACKAGES.lock().unwrap();
+    let packages = packages.get_or_insert_with(|| {
+        let mut paths: Vec<PathBuf> = fs::read_dir(mods_dir())
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_default();
+        paths.sort();
+        let mut packages = Vec::new();
+        for path in paths.iter().filter(|path| is_package(path)) {
+            match load(path) {
+                Ok(package) => packages.push(package),
+                Err(err) => log::error!("Package: load `{}` failed, {}", path.display(), err),
+            }
+        }
+        packages
+    });
+    f(packages)
+}
+
+/// Packages of the mods directory.
+pub fn installed() -> Vec<ModPackage> {
+    with_packages(|packages| packages.clone())
+}
+
+/// Check the package and copy it into the mods directory, a package of the
+/// same name is replaced.
+pub fn install(path: &Path) -> Result<ModPackage, UranusError> {
+    let package = load(path)?;
+    let dir = mods_dir();
+    let dir_name = dir.to_string_lossy().to_string();
+    fs::create_dir_all(&dir).map_err(|err| UranusError::io(&dir_name, err))?;
+
+    let dest = dir.join(path.file_name().unwrap_or_default());
+    if dest != path {
+        fs::copy(path, &dest).map_err(|err| UranusError::io(dest.to_string_lossy(), err))?;
+    }
+
+    with_packages(|packages| {
+        packages.retain(|value| value.name != package.name);
+        packages.push(package.clone());
+    });
+    log::info!(
+        "Package: {} installed to `{}`.",
+        package.name,
+        dest.display()
+    );
+    Ok(package)
+}
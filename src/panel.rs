@@ -1,15 +1,37 @@
 use crate::assemble::Assemble;
-use crate::toast::{self, Status, Toast};
+use crate::components::Charism;
+use crate::config::Config;
+use crate::diff::{self, FileDiff};
+use crate::error::UranusError;
+use crate::keymap::{Keymap, Shortcut};
+use crate::locale::Language;
+use crate::logger::{self, Verbosity};
+use crate::logs::{self, LogTail};
+use crate::package;
+use crate::paths;
+use crate::snapshot::{self, FileChange, Snapshot};
+use crate::themes::{self, UranusTheme};
+use crate::toast::{self, Status};
+use crate::utils::{error_chain, read_from_file};
+use crate::vanilla;
+use crate::version::Compatibility;
+use crate::watcher;
 
 use iced::font::{self, Font};
-use iced::theme::Palette;
+use iced::theme;
 use iced::widget::{
-    self, button, checkbox, column, container, image, row, text, tooltip, Image, Text, Tooltip,
+    self, button, checkbox, column, container, horizontal_space, image, pick_list, row, scrollable,
+    slider, text, text_input, tooltip, Column, Image, Row, Text, Tooltip,
 };
-use iced::{executor, keyboard, subscription, window, Color, Event, Subscription};
+use iced::{clipboard, executor, keyboard, subscription, time, window, Event, Subscription};
 use iced::{Application, Command, Element, Length, Settings, Theme};
 
+use chrono::{DateTime, Local};
+use log::Level;
 use rfd::FileDialog;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 // font for `icon` and `font`
 pub const ICON_FONT: Font = Font::with_name("icons");
@@ -24,152 +46,1562 @@ const FONT_SIZE: f32 = 24.0;
 // tip size and position
 const TIP_SIZE: f32 = 16.0;
 const TIP_POSITION: iced::widget::tooltip::Position = tooltip::Position::FollowCursor;
-// custom color
-const RED_COLOR: iced::Color =
-    Color::from_rgb(120 as f32 / 255.0, 5 as f32 / 255.0, 0 as f32 / 255.0);
-const ORANGE_COLOR: iced::Color =
-    Color::from_rgb(255 as f32 / 255.0, 120 as f32 / 255.0, 5 as f32 / 255.0);
-const GREEN_COLOR: iced::Color =
-    Color::from_rgb(0 as f32 / 255.0, 180 as f32 / 255.0, 150 as f32 / 255.0);
+// width of the slider of a charism value
+const PARAM_WIDTH: f32 = 120.0;
+// images embedded in the binary
+const ICON_IMAGE: &[u8] = include_bytes!("../resources/zagreus-icon-1.jpg");
+const BANNER_IMAGE: &[u8] = include_bytes!("../resources/zagreus-icon-2.jpg");
+// smallest size of the window
+const MIN_WIDTH: u32 = 480;
+const MIN_HEIGHT: u32 = 480;
+// groups are listed in two columns from this width
+const WIDE_WIDTH: u32 = 1000;
+// the rows of the header are split in two below this width
+const NARROW_WIDTH: u32 = 800;
+// banner is hidden below this height
+const BANNER_HEIGHT: u32 = 720;
+const BANNER_WIDTH: u32 = 500;
+// position reported by windows for a minimized window
+const MINIMIZED_POSITION: i32 = -32000;
+// toasts shown at the same time
+const MAX_TOASTS: usize = 4;
+// seconds the toasts with actions, e.g. Undo, stay at least
+const ACTION_TOAST_TIMEOUT: u64 = 10;
+// id of the search input
+const SEARCH_INPUT: &str = "search";
+// levels of the log filter
+const LOG_LEVELS: [Level; 5] = [
+    Level::Error,
+    Level::Warn,
+    Level::Info,
+    Level::Debug,
+    Level::Trace,
+];
+// interval to read the new lines of log file
+const LOG_INTERVAL: Duration = Duration::from_secs(1);
+// wait for the other program to finish writing before checking the files
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
 
 //  run iced
 pub fn pannel_main() -> iced::Result {
-    let mut settings = Settings::default();
+    let config = Config::load();
+    let window_config = config.window;
+    let mut settings = Settings::with_flags(config);
 
+    // size and position of the last session
     let mut window_settings: window::Settings = window::Settings::default();
-    window_settings.size = (600, 800);
-    window_settings.resizable = false;
+    window_settings.size = (
+        window_config.width.max(MIN_WIDTH),
+        window_config.height.max(MIN_HEIGHT),
+    );
+    window_settings.min_size = Some((MIN_WIDTH, MIN_HEIGHT));
+    window_settings.resizable = true;
+    if let (Some(x), Some(y)) = (window_config.x, window_config.y) {
+        window_settings.position = window::Position::Specific(x, y);
+    }
     settings.window = window_settings;
+    // keep the size and position before closing
+    settings.exit_on_close_request = false;
 
     // setting window icon
-    match window::icon::from_file("resources/zagreus-icon-1.jpg") {
+    match window::icon::from_file_data(ICON_IMAGE, None) {
         Ok(icon) => settings.window.icon = Some(icon),
         Err(_) => settings.window.icon = None,
     }
     Pannel::run(settings)
 }
 
+/// Which charisms are listed according to their checked state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum CharismFilter {
+    #[default]
+    All,
+    Applied,
+    Available,
+}
+
+impl CharismFilter {
+    const ALL: [CharismFilter; 3] = [
+        CharismFilter::All,
+        CharismFilter::Applied,
+        CharismFilter::Available,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            CharismFilter::All => "All",
+            CharismFilter::Applied => "Applied",
+            CharismFilter::Available => "Available",
+        }
+    }
+}
+
+type Toast = toast::Toast<Message>;
+
+/// Pane shown in the window.
+#[derive(Default)]
+enum Pane {
+    #[default]
+    Charisms,
+    // charism index and its file diffs
+    Preview(usize, Vec<FileDiff>),
+    History,
+    Logs,
+    Snapshots,
+    // title, snapshot compared with the Scripts folder and the file diffs
+    SnapshotDiff(String, Option<String>, Vec<FileDiff>),
+    Shortcuts,
+}
+
 #[derive(Default)]
 struct Pannel<'a> {
-    always_fishing_point_checkbox: bool,
-    catch_better_fish_checkbox: bool,
-    easier_to_pick_up_checkbox: bool,
-    gifit_trait_quick_upgrade_checkbox: bool,
-    free_store_exchange_checkbox: bool,
-    always_hero_raity_trait_checkbox: bool,
+    // checked state of charisms, in the order of `Assemble::charisms`
+    charism_checkboxes: Vec<bool>,
+    // groups folded in the view
+    collapsed_groups: Vec<String>,
+    // keyword of the search input and the checked state filter
+    search: String,
+    charism_filter: CharismFilter,
+    pane: Pane,
+    // charism chosen by the keyboard
+    selected: Option<usize>,
+    // changes of each action as (index, value), for undo and redo
+    undo_stack: Vec<Vec<(usize, bool)>>,
+    redo_stack: Vec<Vec<(usize, bool)>>,
+    keymap: Keymap,
+
+    roolback_checkbox: bool,
+    pub assemble: Assemble<'a>,
+    // name of the preset to save
+    preset_name: String,
+    toasts: Vec<Toast>,
+    next_toast_id: usize,
+    // every toast of the session with the time it was shown
+    history: Vec<(DateTime<Local>, Toast)>,
+    history_filter: Option<Status>,
+    // entries of the log file with the level and keyword filter
+    log_tail: LogTail,
+    log_level: Option<Level>,
+    log_search: String,
+    // built-in and user themes, the chosen one is kept in config
+    themes: Vec<UranusTheme>,
+    theme: UranusTheme,
+    // hashes of the tracked files after the last change of uranus, files
+    // changed since then by other programs and the time of the last change
+    file_hashes: HashMap<String, u64>,
+    changed_files: HashSet<PathBuf>,
+    last_change: Option<Instant>,
+    // snapshots of the Scripts folder and the ids chosen to compare
+    snapshots: Vec<Snapshot>,
+    compared_snapshots: Vec<String>,
+    config: Config,
+}
+
+/// The widgets in one row, or the second ones in a row below on narrow windows.
+fn split_row<'a>(
+    narrow: bool,
+    first: Vec<Element<'a, Message>>,
+    second: Vec<Element<'a, Message>>,
+) -> Element<'a, Message> {
+    if narrow {
+        column![
+            Row::with_children(first)
+                .spacing(12)
+                .align_items(iced::Alignment::Center),
+            Row::with_children(second)
+                .spacing(12)
+                .align_items(iced::Alignment::Center)
+        ]
+        .spacing(12)
+        .into()
+    } else {
+        Row::with_children(first.into_iter().chain(second).collect())
+            .spacing(24)
+            .align_items(iced::Alignment::Center)
+            .into()
+    }
+}
+
+/// Guidance of the error in the language, followed by its causes.
+fn error_detail(language: Language, err: &UranusError) -> String {
+    format!("{}\n\n{}", language.tr(err.guidance()), error_chain(err))
+}
+
+impl Pannel<'_> {
+    /// According to the value to change the checkbox of the charism at index,
+    /// returns whether the charism was applied or rolled back.
+    pub fn checkbox_selector(&mut self, value: bool, index: usize) -> bool {
+        // roolback checkbox should be false
+        self.roolback_checkbox = false;
+        // select corresponding checkbox and charism
+        let charisms = self.assemble.charisms();
+        let charism = match charisms.get(index) {
+            Some(charism) if index < self.charism_checkboxes.len() => charism.borrow(),
+            _ => {
+                return false;
+            }
+        };
+        let mut changed = false;
+        let language = self.config.language;
+        let name = language.tr(&charism.name);
+        let compatibility = self
+            .assemble
+            .compatibility(&charism, self.config.verified_by_user(&charism.name));
+        // the charisms sharing its files are applied again with it
+        let mut result = Ok(());
+        for (other, err) in self.assemble.switch(index, value, &self.charism_checkboxes) {
+            if other == index {
+                result = Err(err);
+                continue;
+            }
+            log::error!("Pannel: {} apply again failed!", err);
+            self.charism_checkboxes[other] = false;
+            let other_name = language.tr(&charisms[other].borrow().name).to_string();
+            let toast = Toast::new(
+                language.tr("Apply"),
+                format!("{} {}", other_name, language.tr("failed")),
+                Status::Danger,
+            )
+            .detail(error_detail(language, &err))
+            .action(language.tr("Retry"), Message::CharismChecked(other, true))
+            .action(language.tr("Show log"), Message::LogsOpened);
+            self.push_toast(toast);
+        }
+        // change checkbox to value
+        let checkbox_ptr = &mut self.charism_checkboxes[index];
+        *checkbox_ptr = value;
+        let toast = if value {
+            // checked, apply
+            match result {
+                Ok(_) => {
+                    log::info!("Pannel: {} apply success.", charism.name);
+                    changed = true;
+                    let toast = Toast::new(
+                        language.tr("Apply"),
+                        format!("{} {}", name, language.tr("success")),
+                        Status::Success,
+                    )
+                    .action(language.tr("Undo"), Message::CharismChecked(index, false));
+                    if compatibility == Compatibility::Untested {
+                        log::warn!(
+                            "Pannel: {} untested on {}.",
+                            charism.name,
+                            self.assemble.fingerprint
+                        );
+                        toast
+                            .detail(format!(
+                                "{} {}",
+                                language.tr(compatibility.label()),
+                                self.assemble.fingerprint
+                            ))
+                            .action(
+                                language.tr("Mark verified"),
+                                Message::VersionVerified(index),
+                            )
+                    } else {
+                        toast
+                    }
+                }
+                Err(err) => {
+                    log::error!("Pannel: {} apply failed!", err);
+                    *checkbox_ptr = false;
+                    Toast::new(
+                        language.tr("Apply"),
+                        format!("{} {}", name, language.tr("failed")),
+                        Status::Danger,
+                    )
+                    .detail(error_detail(language, &err))
+                    .action(language.tr("Retry"), Message::CharismChecked(index, true))
+                    .action(language.tr("Open diff"), Message::PreviewOpened(index))
+                    .action(language.tr("Show log"), Message::LogsOpened)
+                }
+            }
+        } else {
+            // uncheck, rollback
+            match result {
+                Ok(_) => {
+                    log::info!("Pannel: {} rollback success.", charism.name);
+                    changed = true;
+                    Toast::new(
+                        language.tr("RollBack"),
+                        format!("{} {}", name, language.tr("success")),
+                        Status::Success,
+                    )
+                    .action(language.tr("Undo"), Message::CharismChecked(index, true))
+                }
+                Err(err) => {
+                    log::error!("Pannel: {} rollback failed!", err);
+                    Toast::new(
+                        language.tr("RollBack"),
+                        format!("{} {}", name, language.tr("failed")),
+                        Status::Danger,
+                    )
+                    .detail(error_detail(language, &err))
+                    .action(language.tr("Retry"), Message::CharismChecked(index, false))
+                    .action(language.tr("Show log"), Message::LogsOpened)
+                }
+            }
+        };
+        self.push_toast(toast);
+        self.snapshot_files();
+        self.save_enabled();
+        changed
+    }
+
+    /// The Scripts folder of the installation in use.
+    fn hades_path(&self) -> &str {
+        self.assemble.hades_path()
+    }
+
+    /// Register the Scripts folder of the game and use it.
+    fn add_installation(&mut self, path: String) {
+        let name = self.config.add_installation(path);
+        self.use_installation(name);
+    }
+
+    /// Use the installation of the name, its mods are checked as they were
+    /// left, none is used if there is no such installation.
+    fn use_installation(&mut self, name: String) {
+        self.config.installation = name;
+        let installation = self
+            .config
+            .current_installation()
+            .cloned()
+            .unwrap_or_default();
+
+        //  create a new assemble, otherwise the backup_files is the same as before
+        self.assemble = Assemble::new();
+        if !installation.hades_path.is_empty() {
+            self.assemble.set_installation(installation.clone());
+            self.assemble.assemble_all();
+        }
+        self.charism_checkboxes = self
+            .assemble
+            .charisms()
+            .iter()
+            .map(|charism| {
+                installation
+                    .enabled
+                    .iter()
+                    .any(|name| *name == charism.borrow().name)
+            })
+            .collect();
+        // the changes belong to the charisms of the old installation
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.changed_files.clear();
+        self.compared_snapshots.clear();
+        self.snapshot_files();
+        self.save_config();
+        if installation.hades_path.is_empty() {
+            self.snapshots.clear();
+            return;
+        }
+        log::info!(
+            "Change installation to {} of `{}`",
+            installation.name,
+            installation.hades_path
+        );
+
+        // keep a version of the files before they are modified
+        match snapshot::create_if_changed(self.hades_path(), "picked") {
+            Ok(_) => {
+                self.prune_snapshots();
+            }
+            Err(err) => log::error!("Pannel: snapshot `{}` failed, {}", self.hades_path(), err),
+        }
+        self.snapshots = snapshot::list(Some(self.hades_path()));
+    }
+
+    /// Names of the checked charisms.
+    fn checked_names(&self) -> Vec<String> {
+        self.assemble
+            .charisms()
+            .iter()
+            .zip(&self.charism_checkboxes)
+            .filter(|(_, checked)| **checked)
+            .map(|(charism, _)| charism.borrow().name.to_string())
+            .collect()
+    }
+
+    /// Keep the checked charisms as the enabled mods of the installation.
+    fn save_enabled(&mut self) {
+        let enabled = self.checked_names();
+        match self.config.current_installation_mut() {
+            Some(installation) if installation.enabled != enabled => {
+                installation.enabled = enabled;
+            }
+            _ => return,
+        }
+        self.save_config();
+    }
+
+    /// Set the value of the charism at index, kept for the installation.
+    fn set_param(&mut self, index: usize, value: f64) {
+        let charisms = self.assemble.charisms();
+        let charism = match charisms.get(index) {
+            Some(charism) => charism.borrow(),
+            None => return,
+        };
+        if let Some(param) = &charism.param {
+            param.set(value);
+            if let Some(installation) = self.config.current_installation_mut() {
+                installation
+                    .params
+                    .insert(charism.name.to_string(), param.get());
+            }
+        }
+    }
+
+    /// Save the value of the charism at index, the applied charism follows it.
+    fn save_param(&mut self, index: usize) {
+        self.save_config();
+        if self.charism_checkboxes.get(index) == Some(&true) && self.check_hades_path() {
+            self.checkbox_selector(true, index);
+        }
+    }
+
+    fn save_config(&self) {
+        if let Err(err) = self.config.save() {
+            log::error!("Pannel: save config failed, {}", err);
+        }
+    }
+
+    /// Check the charisms of the preset and uncheck the others, as one action.
+    fn apply_preset(&mut self, name: &str) {
+        let preset = match self
+            .config
+            .current_installation()
+            .and_then(|installation| installation.presets.get(name))
+        {
+            Some(preset) => preset.clone(),
+            None => return,
+        };
+        let names: Vec<String> = self
+            .assemble
+            .charisms()
+            .iter()
+            .map(|charism| charism.borrow().name.to_string())
+            .collect();
+        let mut changes = Vec::new();
+        for (index, name) in names.into_iter().enumerate() {
+            let value = preset.iter().any(|value| *value == name);
+            if self.charism_checkboxes[index] != value && self.checkbox_selector(value, index) {
+                changes.push((index, value));
+            }
+        }
+        self.record(changes);
+    }
+
+    /// Remove the snapshots out of the retention of config.
+    fn prune_snapshots(&mut self) -> usize {
+        let pruned = match snapshot::prune(self.config.snapshots) {
+            Ok(pruned) => pruned,
+            Err(err) => {
+                log::error!("Pannel: prune snapshots failed, {}", err);
+                Vec::new()
+            }
+        };
+        self.compared_snapshots
+            .retain(|id| !pruned.iter().any(|value| value == id));
+        self.snapshots = snapshot::list(Some(self.hades_path()));
+        pruned.len()
+    }
+
+    /// Open the diff pane of the compared snapshots, the older is compared
+    /// with the newer one, or a single one with the Scripts folder.
+    fn open_snapshot_diff(&mut self) {
+        let language = self.config.language;
+        let mut compared: Vec<&Snapshot> = self
+            .snapshots
+            .iter()
+            .filter(|snapshot| self.compared_snapshots.contains(&snapshot.id))
+            .collect();
+        compared.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let file_diffs = |old: &Snapshot, new: Option<&Snapshot>| {
+            let changes = match new {
+                Some(new) => snapshot::compare(old, new),
+                None => snapshot::compare_current(old)?,
+            };
+            let mut file_diffs = Vec::new();
+            for (name, change) in changes {
+                let before = match change {
+                    FileChange::Added => String::new(),
+                    _ => old.read(&name)?,
+                };
+                let after = match (change, new) {
+                    (FileChange::Removed, _) => String::new(),
+                    (_, Some(new)) => new.read(&name)?,
+                    (_, None) => read_from_file(&old.current_path(&name))?,
+                };
+                file_diffs.push(FileDiff::new(&name, &before, &after));
+            }
+            Ok::<_, UranusError>(file_diffs)
+        };
+        let (title, restore_from, result) = match compared.as_slice() {
+            [old] => (
+                format!("{} → {}", old.id, language.tr("Current")),
+                Some(old.id.clone()),
+                file_diffs(*old, None),
+            ),
+            [old, new] => (
+                format!("{} → {}", old.id, new.id),
+                None,
+                file_diffs(*old, Some(*new)),
+            ),
+            _ => return,
+        };
+
+        match result {
+            Ok(file_diffs) => self.pane = Pane::SnapshotDiff(title, restore_from, file_diffs),
+            Err(err) => {
+                log::error!("Pannel: compare snapshots failed, {}", err);
+                let toast = Toast::new(
+                    language.tr("Compare"),
+                    format!("{} {}", title, language.tr("failed")),
+                    Status::Danger,
+                )
+                .detail(error_detail(language, &err))
+                .action(language.tr("Show log"), Message::LogsOpened);
+                self.push_toast(toast);
+            }
+        }
+    }
+
+    /// Restore the file, or the whole Scripts folder, to the snapshot and
+    /// check again which charisms are applied.
+    fn restore_snapshot(&mut self, id: String, file: Option<String>) {
+        let language = self.config.language;
+        let result =
+            snapshot::find(&id).and_then(|value| snapshot::restore(&value, file.as_deref()));
+        let toast = match result {
+            Ok(restored) => Toast::new(
+                language.tr("Restore"),
+                format!(
+                    "{} {} {}",
+                    id,
+                    restored.len(),
+                    language.tr("files restored")
+                ),
+                Status::Success,
+            ),
+            Err(err) => {
+                log::error!("Pannel: restore snapshot {} failed, {}", id, err);
+                Toast::new(
+                    language.tr("Restore"),
+                    format!("{} {}", id, language.tr("failed")),
+                    Status::Danger,
+                )
+                .detail(error_detail(language, &err))
+                .action(language.tr("Show log"), Message::LogsOpened)
+            }
+        };
+        self.push_toast(toast);
+        // the restored files are not the changes of other programs
+        self.snapshot_files();
+        self.refresh_states();
+        self.snapshots = snapshot::list(Some(self.hades_path()));
+    }
+
+    /// Restore every backed up file of the installation and remove the files
+    /// created by the mods, then check again which charisms are applied.
+    fn restore_vanilla(&mut self) {
+        let language = self.config.language;
+        let toast = match vanilla::restore(self.hades_path()) {
+            Ok(report) if report.is_empty() => Toast::new(
+                language.tr("Restore vanilla"),
+                language.tr("No backups").to_string(),
+                Status::Primary,
+            ),
+            Ok(report) => Toast::new(
+                language.tr("Restore vanilla"),
+                format!(
+                    "{} {}, {} {}",
+                    report.restored.len() + report.legacy.len(),
+                    language.tr("files restored"),
+                    report.removed.len(),
+                    language.tr("files removed")
+                ),
+                Status::Success,
+            )
+            .detail(report.lines().join("\n")),
+            Err(err) => {
+                log::error!("Pannel: restore vanilla failed, {}", err);
+                Toast::new(
+                    language.tr("Restore vanilla"),
+                    language.tr("failed").to_string(),
+                    Status::Danger,
+                )
+                .detail(error_detail(language, &err))
+                .action(language.tr("Show log"), Message::LogsOpened)
+            }
+        };
+        self.push_toast(toast);
+        // the recorded changes no longer match the files
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.snapshot_files();
+        self.refresh_states();
+        self.snapshots = snapshot::list(Some(self.hades_path()));
+    }
+
+    /// Check which charisms are found in their files.
+    fn refresh_states(&mut self) {
+        for (index, charism) in self.assemble.charisms().iter().enumerate() {
+            let charism = charism.borrow();
+            match charism.is_applied() {
+                Ok(Some(applied)) => self.charism_checkboxes[index] = applied,
+                // the files are the same either way, keep the checkbox
+                Ok(None) => {}
+                Err(err) => log::error!("Pannel: check {} failed, {}", charism.name, err),
+            }
+        }
+        self.save_enabled();
+    }
+
+    /// Install the dropped mod package, or use the Scripts folder resolved
+    /// from the dropped folder or file.
+    fn file_dropped(&mut self, path: PathBuf) {
+        let language = self.config.language;
+        if package::is_package(&path) {
+            let toast = match package::install(&path) {
+                Ok(package) => {
+                    let name = language.tr(&package.name).to_string();
+                    let body = if self.assemble.add_package(package) {
+                        self.charism_checkboxes.push(false);
+                        format!("{} {}", name, language.tr("success"))
+                    } else {
+                        format!("{} {}", name, language.tr("takes effect after restart"))
+                    };
+                    Toast::new(language.tr("Install"), body, Status::Success)
+                }
+                Err(err) => {
+                    log::error!("Pannel: install `{}` failed, {}", path.display(), err);
+                    Toast::new(
+                        language.tr("Install"),
+                        format!("{} {}", path.display(), language.tr("failed")),
+                        Status::Danger,
+                    )
+                    .detail(error_detail(language, &err))
+                }
+            };
+            self.push_toast(toast);
+            return;
+        }
+
+        match paths::scripts_dir(&path) {
+            Some(scripts_dir) => self.add_installation(scripts_dir.display().to_string()),
+            None => {
+                log::warn!("Pannel: no Scripts folder in `{}`.", path.display());
+                self.push_toast(Toast::new(
+                    language.tr("Tips"),
+                    format!("{} {}", language.tr("No Scripts folder in"), path.display()),
+                    Status::Primary,
+                ));
+            }
+        }
+    }
+
+    /// Keep the hashes of the files tracked by the charisms, so only the
+    /// changes of other programs are noticed by the watcher.
+    fn snapshot_files(&mut self) {
+        self.file_hashes.clear();
+        for charism in self.assemble.charisms() {
+            for file in charism.borrow().files() {
+                if let Some(hash) = watcher::hash_file(Path::new(&file)) {
+                    self.file_hashes.insert(file, hash);
+                }
+            }
+        }
+    }
+
+    /// Check the files changed by other programs, re-evaluate the charisms
+    /// tracking them and tell the user which mods were lost or restored.
+    fn check_changed_files(&mut self) {
+        let changed_files: Vec<PathBuf> = self.changed_files.drain().collect();
+        let mut files = Vec::new();
+        for (file, hash) in self.file_hashes.iter_mut() {
+            let path = Path::new(file.as_str());
+            // the watcher reports the full path, the charisms may not
+            if !changed_files
+                .iter()
+                .any(|changed| changed.file_name() == path.file_name())
+            {
+                continue;
+            }
+            let new_hash = watcher::hash_file(path).unwrap_or_default();
+            if new_hash != *hash {
+                *hash = new_hash;
+                files.push(file.clone());
+            }
+        }
+        if files.is_empty() {
+            return;
+        }
+        log::warn!("Pannel: {:?} changed by another program.", files);
+
+        let mut lost = Vec::new();
+        for (index, charism) in self.assemble.charisms().iter().enumerate() {
+            let charism = charism.borrow();
+            if !charism.files().iter().any(|file| files.contains(file)) {
+                continue;
+            }
+            let applied = match charism.is_applied() {
+                Ok(applied) => applied,
+                Err(err) => {
+                    log::error!("Pannel: check {} failed, {}", charism.name, err);
+                    Some(false)
+                }
+            };
+            if let Some(checkbox) = self.charism_checkboxes.get_mut(index) {
+                // the files are the same either way, keep the checkbox
+                let applied = applied.unwrap_or(*checkbox);
+                if *checkbox && !applied {
+                    lost.push(index);
+                }
+                *checkbox = applied;
+            }
+        }
+        self.save_enabled();
+
+        let language = self.config.language;
+        let names: Vec<String> = files
+            .iter()
+            .map(|file| {
+                Path::new(file)
+                    .file_name()
+                    .map_or(file.clone(), |name| name.to_string_lossy().to_string())
+            })
+            .collect();
+        let mut toast = Toast::new(
+            language.tr("Tips"),
+            format!(
+                "{} {}",
+                names.join(", "),
+                language.tr("was changed by another program")
+            ),
+            Status::Primary,
+        );
+        if !lost.is_empty() {
+            toast = toast.action(language.tr("Re-apply"), Message::Reapplied(lost));
+        }
+        self.push_toast(toast.action(language.tr("Refresh"), Message::StatesRefreshed));
+    }
+
+    /// Keep the changes of an action for undo, a new action drops the redo.
+    fn record(&mut self, changes: Vec<(usize, bool)>) {
+        if !changes.is_empty() {
+            self.undo_stack.push(changes);
+            self.redo_stack.clear();
+        }
+    }
+
+    /// Indexes of the charisms listed in the view, in their order.
+    fn listed_indexes(&self) -> Vec<usize> {
+        self.assemble
+            .groups()
+            .into_iter()
+            .filter(|group| !self.collapsed_groups.iter().any(|value| value == group))
+            .flat_map(|group| self.visible_indexes(&group))
+            .collect()
+    }
+
+    /// Run the action bound to a key.
+    fn shortcut(&mut self, shortcut: Shortcut) -> Command<Message> {
+        let listing = matches!(self.pane, Pane::Charisms);
+        match shortcut {
+            Shortcut::PickFolder => return self.update(Message::FloderPickPressed),
+            Shortcut::Toggle if listing => {
+                if let Some(index) = self.selected {
+                    let value = !self.charism_checkboxes[index];
+                    return self.update(Message::CharismChecked(index, value));
+                }
+            }
+            Shortcut::Next | Shortcut::Previous if listing => {
+                let indexes = self.listed_indexes();
+                let position = self
+                    .selected
+                    .and_then(|index| indexes.iter().position(|&value| value == index));
+                self.selected = match (shortcut, position) {
+                    (_, None) => indexes.first().copied(),
+                    (Shortcut::Next, Some(position)) => {
+                        indexes.get(position + 1).or(indexes.last()).copied()
+                    }
+                    (_, Some(position)) => indexes.get(position.saturating_sub(1)).copied(),
+                };
+            }
+            Shortcut::RollbackAll => return self.update(Message::RollbackChecked(true)),
+            Shortcut::Undo => return self.update(Message::Undo),
+            Shortcut::Redo => return self.update(Message::Redo),
+            Shortcut::Preview => {
+                if let Some(index) = self.selected {
+                    return self.update(Message::PreviewOpened(index));
+                }
+            }
+            Shortcut::Search => {
+                self.pane = Pane::Charisms;
+                return text_input::focus(text_input::Id::new(SEARCH_INPUT));
+            }
+            Shortcut::Close => self.pane = Pane::Charisms,
+            Shortcut::CheatSheet => {
+                self.pane = match self.pane {
+                    Pane::Shortcuts => Pane::Charisms,
+                    _ => Pane::Shortcuts,
+                }
+            }
+            _ => {}
+        }
+        Command::none()
+    }
+
+    /// Give the toast an unique id and show it, its actions also close it.
+    fn push_toast(&mut self, mut toast: Toast) {
+        toast.id = self.next_toast_id;
+        self.next_toast_id += 1;
+        for action in toast.actions.iter_mut() {
+            action.message = Message::ToastAction(toast.id, Box::new(action.message.clone()));
+        }
+        self.history.push((Local::now(), toast.clone()));
+        self.toasts.push(toast);
+    }
+
+    /// Open the preview pane of the charism at index, the origin backup is compared
+    /// with the current content if applied, otherwise the would-be content.
+    fn open_preview(&mut self, index: usize) {
+        let charisms = self.assemble.charisms();
+        let charism = match charisms.get(index) {
+            Some(charism) => charism.borrow(),
+            None => return,
+        };
+        let applied = self.charism_checkboxes[index];
+
+        let file_diffs = charism.preview().and_then(|previews| {
+            previews
+                .into_iter()
+                .map(|(file, before, after)| -> Result<FileDiff, UranusError> {
+                    let after = if applied {
+                        read_from_file(&file)?
+                    } else {
+                        after
+                    };
+                    Ok(FileDiff::new(&file, &before, &after))
+                })
+                .collect()
+        });
+
+        match file_diffs {
+            Ok(file_diffs) => self.pane = Pane::Preview(index, file_diffs),
+            Err(err) => {
+                log::error!("Pannel: {} preview failed, {}", charism.name, err);
+                let language = self.config.language;
+                let toast = Toast::new(
+                    language.tr("Diff"),
+                    format!("{} {}", language.tr(&charism.name), language.tr("failed")),
+                    Status::Danger,
+                )
+                .detail(error_detail(language, &err))
+                .action(language.tr("Retry"), Message::PreviewOpened(index));
+                self.push_toast(toast);
+            }
+        }
+    }
+
+    /// Check the hades path is picked, otherwise toast a tip.
+    fn check_hades_path(&mut self) -> bool {
+        if self.hades_path().is_empty() {
+            log::warn!("Pick Floder First!");
+            let language = self.config.language;
+            self.push_toast(Toast::new(
+                language.tr("Tips"),
+                language.tr("Pick Floder First"),
+                Status::Primary,
+            ));
+            return false;
+        }
+        true
+    }
+
+    /// Whether the charism at index passes the search keyword and filter.
+    fn charism_visible(&self, index: usize, charism: &Charism) -> bool {
+        let checked = self.charism_checkboxes[index];
+        match self.charism_filter {
+            CharismFilter::Applied if !checked => return false,
+            CharismFilter::Available if checked => return false,
+            _ => {}
+        }
+
+        let keyword = self.search.trim().to_lowercase();
+        if keyword.is_empty() {
+            return true;
+        }
+
+        // search in both english and translated texts, and the target file names
+        let language = self.config.language;
+        let mut fields = vec![
+            charism.name.to_string(),
+            charism.description.to_string(),
+            charism.group.to_string(),
+            language.tr(&charism.name).to_string(),
+            language.tr(&charism.description).to_string(),
+            language.tr(&charism.group).to_string(),
+        ];
+        for file in charism.files() {
+            if let Some(file_name) = Path::new(&file).file_name() {
+                fields.push(file_name.to_string_lossy().to_string());
+            }
+        }
+        fields
+            .iter()
+            .any(|field| field.to_lowercase().contains(&keyword))
+    }
+
+    /// Indexes of the charisms of the group which are visible.
+    fn visible_indexes(&self, group: &str) -> Vec<usize> {
+        let charisms = self.assemble.charisms();
+        self.assemble
+            .group_indexes(group)
+            .into_iter()
+            .filter(|&index| self.charism_visible(index, &charisms[index].borrow()))
+            .collect()
+    }
+
+    /// A checkbox with the name and description tip of the charism at index.
+    fn charism_row(&self, index: usize, charism: &Charism) -> Element<Message> {
+        let language = self.config.language;
+
+        let charism_checkbox = checkbox("", self.charism_checkboxes[index], move |value| {
+            Message::CharismChecked(index, value)
+        })
+        .font(YY_FONT);
+
+        let charism_tip = Tooltip::new(
+            Text::new(language.tr(&charism.name).to_string())
+                .font(YY_FONT)
+                .size(FONT_SIZE)
+                .style(self.theme.accent),
+            language.tr(&charism.description).to_string(),
+            TIP_POSITION,
+        )
+        // .gap(10)
+        .font(YY_FONT)
+        // .padding(10)
+        .size(TIP_SIZE);
+
+        // open the preview pane
+        let diff_button = button(Text::new(language.tr("Diff")).font(YY_FONT).size(TIP_SIZE))
+            .style(theme::Button::Text)
+            .on_press(Message::PreviewOpened(index));
+
+        let mut charism_row = row![
+            horizontal_space(Length::Fixed(FONT_SIZE)),
+            charism_checkbox,
+            charism_tip,
+            diff_button
+        ]
+        .align_items(iced::Alignment::Center);
+
+        // the value of the charism, applied again when released or chosen
+        if let Some(param) = charism
+            .param
+            .as_ref()
+            .filter(|param| !param.choices.is_empty())
+        {
+            let choices: Vec<&'static str> = param
+                .choices
+                .iter()
+                .map(|choice| language.tr(choice))
+                .collect();
+            let selected = choices.get(param.get().round() as usize).copied();
+            let param_list = pick_list(choices.clone(), selected, move |choice| {
+                let position = choices.iter().position(|value| *value == choice);
+                Message::ParamChosen(index, position.unwrap_or_default() as f64)
+            })
+            .placeholder(language.tr(param.label))
+            .text_size(TIP_SIZE)
+            .font(YY_FONT);
+            charism_row = charism_row
+                .push(horizontal_space(Length::Fixed(TIP_SIZE)))
+                .push(param_list);
+        } else if let Some(param) = &charism.param {
+            let param_slider = slider(param.min..=param.max, param.get(), move |value| {
+                Message::ParamChanged(index, value)
+            })
+            .step(param.step)
+            .on_release(Message::ParamReleased(index))
+            .width(Length::Fixed(PARAM_WIDTH));
+            let param_text = Text::new(format!(
+                "{} {}",
+                language.tr(param.label),
+                language.tr(&param.text())
+            ))
+            .font(YY_FONT)
+            .size(TIP_SIZE);
+            charism_row = charism_row
+                .push(horizontal_space(Length::Fixed(TIP_SIZE)))
+                .push(param_slider)
+                .push(param_text);
+        }
+
+        // warn the mod was verified on other builds of the game only
+        let compatibility = self
+            .assemble
+            .compatibility(charism, self.config.verified_by_user(&charism.name));
+        if compatibility == Compatibility::Untested {
+            let untested_tip = Tooltip::new(
+                Text::new(language.tr(compatibility.label()))
+                    .font(YY_FONT)
+                    .size(TIP_SIZE)
+                    .style(self.theme.palette.danger),
+                format!(
+                    "{} {}",
+                    language.tr("Fingerprint"),
+                    self.assemble.fingerprint
+                ),
+                TIP_POSITION,
+            )
+            .font(YY_FONT)
+            .size(TIP_SIZE);
+            charism_row = charism_row.push(untested_tip);
+        }
+        // a mod telling no build can be marked once it is applied
+        let checked = self.charism_checkboxes[index];
+        if compatibility == Compatibility::Untested
+            || (compatibility == Compatibility::Undeclared && checked)
+        {
+            let verify_button = button(
+                Text::new(language.tr("Mark verified"))
+                    .font(YY_FONT)
+                    .size(TIP_SIZE),
+            )
+            .style(theme::Button::Text)
+            .on_press(Message::VersionVerified(index));
+            charism_row = charism_row.push(verify_button);
+        }
+
+        // mark the charism chosen by the keyboard
+        let charism_row = container(charism_row).width(Length::Fill);
+        if self.selected == Some(index) {
+            charism_row.style(theme::Container::Box).into()
+        } else {
+            charism_row.into()
+        }
+    }
+
+    /// The file diffs of the charism with a close button.
+    fn preview_view<'a>(
+        &'a self,
+        index: usize,
+        file_diffs: &'a [FileDiff],
+    ) -> Element<'a, Message> {
+        let language = self.config.language;
+        let charism = self.assemble.charisms()[index].clone();
+        let name = language.tr(&charism.borrow().name).to_string();
+
+        let title = text(name)
+            .size(FONT_SIZE)
+            .font(YY_FONT)
+            .style(self.theme.accent);
+        let close_button = button(Text::new(language.tr("Close")).font(YY_FONT).size(TIP_SIZE))
+            .on_press(Message::PaneClosed);
+        let header = row![title, horizontal_space(Length::Fill), close_button]
+            .align_items(iced::Alignment::Center);
+
+        column![
+            header,
+            diff::view(file_diffs, diff::CodeColors::new(&self.theme))
+        ]
+        .spacing(12)
+        .padding(12)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+    }
+
+    /// Cheat sheet of the key bindings.
+    fn shortcuts_view(&self) -> Element<Message> {
+        let language = self.config.language;
+
+        let title = text(language.tr("Shortcuts"))
+            .size(FONT_SIZE)
+            .font(YY_FONT)
+            .style(self.theme.accent);
+        let close_button = button(Text::new(language.tr("Close")).font(YY_FONT).size(TIP_SIZE))
+            .on_press(Message::PaneClosed);
+        let header = row![title, horizontal_space(Length::Fill), close_button]
+            .align_items(iced::Alignment::Center);
+
+        let mut bindings = column![].spacing(8);
+        for (shortcut, binding) in self.keymap.bindings() {
+            bindings = bindings.push(
+                row![
+                    text(binding.to_string())
+                        .font(Font::MONOSPACE)
+                        .size(TIP_SIZE)
+                        .width(Length::Fixed(160.0)),
+                    text(language.tr(shortcut.label()))
+                        .font(YY_FONT)
+                        .size(TIP_SIZE)
+                ]
+                .spacing(12),
+            );
+        }
+        column![header, scrollable(bindings).height(Length::Fill)]
+            .spacing(12)
+            .padding(12)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Snapshots of the Scripts folder, the latest first, two of them or one
+    /// with the folder are chosen to compare.
+    fn snapshots_view(&self) -> Element<Message> {
+        let language = self.config.language;
+
+        let title = text(language.tr("Snapshots"))
+            .size(FONT_SIZE)
+            .font(YY_FONT)
+            .style(self.theme.accent);
+        let take_button = button(
+            Text::new(language.tr("Take snapshot"))
+                .font(YY_FONT)
+                .size(TIP_SIZE),
+        )
+        .on_press(Message::SnapshotTaken);
+        let mut compare_button = button(
+            Text::new(language.tr("Compare"))
+                .font(YY_FONT)
+                .size(TIP_SIZE),
+        );
+        if !self.compared_snapshots.is_empty() {
+            compare_button = compare_button.on_press(Message::SnapshotDiffOpened);
+        }
+        let prune_button = button(Text::new(language.tr("Prune")).font(YY_FONT).size(TIP_SIZE))
+            .style(theme::Button::Secondary)
+            .on_press(Message::SnapshotsPruned);
+        let close_button = button(Text::new(language.tr("Close")).font(YY_FONT).size(TIP_SIZE))
+            .on_press(Message::PaneClosed);
+        let header = row![
+            title,
+            horizontal_space(Length::Fill),
+            take_button,
+            compare_button,
+            prune_button,
+            close_button
+        ]
+        .spacing(12)
+        .align_items(iced::Alignment::Center);
+
+        let mut records = column![].spacing(8);
+        for snapshot in self.snapshots.iter().rev() {
+            // chosen to compare
+            let style = if self.compared_snapshots.contains(&snapshot.id) {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            };
+            let id_button = button(text(&snapshot.id).font(Font::MONOSPACE).size(TIP_SIZE))
+                .style(style)
+                .on_press(Message::SnapshotCompared(snapshot.id.clone()));
+            let restore_button = button(
+                Text::new(language.tr("Restore"))
+                    .font(YY_FONT)
+                    .size(TIP_SIZE),
+            )
+            .style(theme::Button::Destructive)
+            .on_press(Message::SnapshotRestored(snapshot.id.clone(), None));
+            let line = row![
+                id_button,
+                text(&snapshot.created).font(YY_FONT).size(TIP_SIZE),
+                text(language.tr(&snapshot.label))
+                    .font(YY_FONT)
+                    .size(TIP_SIZE),
+                text(format!("{} {}", snapshot.files.len(), language.tr("files")))
+                    .font(YY_FONT)
+                    .size(TIP_SIZE),
+                text(&snapshot.fingerprint)
+                    .font(Font::MONOSPACE)
+                    .size(TIP_SIZE),
+                horizontal_space(Length::Fill),
+                restore_button
+            ]
+            .spacing(12)
+            .align_items(iced::Alignment::Center);
+            records = records.push(
+                container(line)
+                    .padding(6)
+                    .width(Length::Fill)
+                    .style(theme::Container::Box),
+            );
+        }
+        if self.snapshots.is_empty() {
+            records = records.push(
+                text(language.tr("No snapshots"))
+                    .size(TIP_SIZE)
+                    .font(YY_FONT),
+            );
+        }
+
+        column![header, scrollable(records).height(Length::Fill)]
+            .spacing(12)
+            .padding(12)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// The file diffs of the compared snapshots, the files of a snapshot
+    /// compared with the Scripts folder can be restored one by one.
+    fn snapshot_diff_view<'a>(
+        &'a self,
+        title: &'a str,
+        restore_from: Option<&'a str>,
+        file_diffs: &'a [FileDiff],
+    ) -> Element<'a, Message> {
+        let language = self.config.language;
+
+        let title = text(title)
+            .size(FONT_SIZE)
+            .font(YY_FONT)
+            .style(self.theme.accent);
+        let close_button = button(Text::new(language.tr("Close")).font(YY_FONT).size(TIP_SIZE))
+            .on_press(Message::SnapshotsOpened);
+        let header = row![title, horizontal_space(Length::Fill), close_button]
+            .align_items(iced::Alignment::Center);
+
+        let mut content = column![header].spacing(12);
+        if file_diffs.is_empty() {
+            content = content.push(text(language.tr("No changes")).size(TIP_SIZE).font(YY_FONT));
+        }
+        let snapshot =
+            restore_from.and_then(|id| self.snapshots.iter().find(|snapshot| snapshot.id == id));
+        if let Some(snapshot) = snapshot {
+            for file_diff in file_diffs {
+                // files added after the snapshot have no copy
+                if snapshot.file(&file_diff.file_name).is_none() {
+                    continue;
+                }
+                let restore_button = button(
+                    Text::new(language.tr("Restore"))
+                        .font(YY_FONT)
+                        .size(TIP_SIZE),
+                )
+                .style(theme::Button::Destructive)
+                .on_press(Message::SnapshotRestored(
+                    snapshot.id.clone(),
+                    Some(file_diff.file_name.clone()),
+                ));
+                content = content.push(
+                    row![
+                        text(&file_diff.file_name)
+                            .font(Font::MONOSPACE)
+                            .size(TIP_SIZE),
+                        restore_button
+                    ]
+                    .spacing(12)
+                    .align_items(iced::Alignment::Center),
+                );
+            }
+        }
+
+        content
+            .push(diff::view(file_diffs, diff::CodeColors::new(&self.theme)))
+            .padding(12)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Notifications of the session filtered by status, the latest first.
+    fn history_view(&self) -> Element<Message> {
+        let language = self.config.language;
+
+        let title = text(language.tr("History"))
+            .size(FONT_SIZE)
+            .font(YY_FONT)
+            .style(self.theme.accent);
+        let close_button = button(Text::new(language.tr("Close")).font(YY_FONT).size(TIP_SIZE))
+            .on_press(Message::PaneClosed);
+        let header = row![title, horizontal_space(Length::Fill), close_button]
+            .align_items(iced::Alignment::Center);
+
+        // filter by status
+        let mut filters = row![].spacing(12);
+        for history_filter in std::iter::once(None).chain(Status::ALL.iter().copied().map(Some)) {
+            let label = match history_filter {
+                Some(status) => language.tr(&status.to_string()).to_string(),
+                None => language.tr("All").to_string(),
+            };
+            let style = if history_filter == self.history_filter {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            };
+            filters = filters.push(
+                button(Text::new(label).font(YY_FONT).size(TIP_SIZE))
+                    .style(style)
+                    .on_press(Message::HistoryFiltered(history_filter)),
+            );
+        }
+
+        let mut records = column![].spacing(8);
+        let mut record_count = 0;
+        for (index, (time, toast)) in self.history.iter().enumerate().rev() {
+            if self
+                .history_filter
+                .map_or(false, |status| status != toast.status)
+            {
+                continue;
+            }
+            record_count += 1;
+
+            let status = container(
+                text(language.tr(&toast.status.to_string()).to_string())
+                    .font(YY_FONT)
+                    .size(TIP_SIZE),
+            )
+            .padding(3)
+            .style(theme::Container::Custom(Box::new(toast.status)));
+            let copy_button = button(Text::new(language.tr("Copy")).font(YY_FONT).size(TIP_SIZE))
+                .style(theme::Button::Text)
+                .on_press(Message::HistoryCopied(index));
+            let line = row![
+                text(time.format("%H:%M:%S")).font(YY_FONT).size(TIP_SIZE),
+                status,
+                text(&toast.title).font(YY_FONT).size(TIP_SIZE),
+                text(&toast.body).font(YY_FONT).size(TIP_SIZE),
+                horizontal_space(Length::Fill),
+                copy_button
+            ]
+            .spacing(12)
+            .align_items(iced::Alignment::Center);
+
+            let mut record = column![line].spacing(4);
+            if let Some(detail) = &toast.detail {
+                record = record.push(text(detail).font(YY_FONT).size(12));
+            }
+            records = records.push(
+                container(record)
+                    .padding(6)
+                    .width(Length::Fill)
+                    .style(theme::Container::Box),
+            );
+        }
+        if record_count == 0 {
+            records = records.push(
+                text(language.tr("No notifications"))
+                    .size(TIP_SIZE)
+                    .font(YY_FONT),
+            );
+        }
+
+        column![header, filters, scrollable(records).height(Length::Fill)]
+            .spacing(12)
+            .padding(12)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Entries of the log file with the level filter and search input.
+    fn logs_view(&self) -> Element<Message> {
+        let language = self.config.language;
+
+        let title = text(language.tr("Logs"))
+            .size(FONT_SIZE)
+            .font(YY_FONT)
+            .style(self.theme.accent);
+        let close_button = button(Text::new(language.tr("Close")).font(YY_FONT).size(TIP_SIZE))
+            .on_press(Message::PaneClosed);
+        let header = row![title, horizontal_space(Length::Fill), close_button]
+            .align_items(iced::Alignment::Center);
+
+        let level_picker = pick_list(
+            &LOG_LEVELS[..],
+            Some(self.log_level.unwrap_or(Level::Info)),
+            Message::LogLevelSelected,
+        )
+        .font(YY_FONT)
+        .text_size(TIP_SIZE);
+        let search_input = text_input(language.tr("Search"), &self.log_search)
+            .on_input(Message::LogSearchChanged)
+            .font(YY_FONT)
+            .size(TIP_SIZE)
+            .padding(5);
+        // level written to the log file
+        let verbosity_picker = pick_list(
+            &Verbosity::ALL[..],
+            Some(self.config.verbosity),
+            Message::VerbositySelected,
+        )
+        .font(YY_FONT)
+        .text_size(TIP_SIZE);
+        let verbosity = row![
+            text(language.tr("Verbosity")).font(YY_FONT).size(TIP_SIZE),
+            verbosity_picker
+        ]
+        .spacing(6)
+        .align_items(iced::Alignment::Center);
+        let filters = row![level_picker, search_input, verbosity]
+            .spacing(12)
+            .align_items(iced::Alignment::Center);
+
+        let content = if self.log_tail.is_available() {
+            let entries = self
+                .log_tail
+                .filtered(self.log_level.unwrap_or(Level::Info), &self.log_search);
+            logs::view(entries)
+        } else {
+            text(language.tr("No log file, log4rs.yaml has no file appender."))
+                .font(YY_FONT)
+                .size(TIP_SIZE)
+                .into()
+        };
+        column![header, filters, content]
+            .spacing(12)
+            .padding(12)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+
+    /// Read the new lines of the log file.
+    fn poll_logs(&mut self) {
+        if let Err(err) = self.log_tail.poll() {
+            log::warn!("Pannel: read log file failed, {}", err);
+        }
+    }
+
+    /// A foldable group header followed by its charisms.
+    fn group_column(&self, group: &str) -> Element<Message> {
+        let language = self.config.language;
+        let charisms = self.assemble.charisms();
+        let indexes = self.visible_indexes(group);
+
+        let collapsed = self.collapsed_groups.iter().any(|value| value == group);
+        let all_checked = indexes.iter().all(|&index| self.charism_checkboxes[index]);
+
+        // fold or unfold the group
+        let fold_button = button(
+            Text::new(if collapsed { "+" } else { "-" })
+                .font(YY_FONT)
+                .size(TIP_SIZE),
+        )
+        .on_press(Message::GroupCollapsed(group.to_string()));
 
-    roolback_checkbox: bool,
-    pub assemble: Assemble<'a>,
-    hades_path: String,
-    toasts: Vec<Toast>,
-}
+        let group_text = text(language.tr(group).to_string())
+            .size(FONT_SIZE)
+            .font(YY_FONT);
 
-impl Pannel<'_> {
-    /// According to the value to select the corresponding checkbox for change.
-    pub fn checkbox_selector(&mut self, value: bool, message: Message) {
-        // roolback checkbox should be false
-        self.roolback_checkbox = false;
-        // select corresponding checkbox and charism
-        let (checkbox_ptr, charism) = match message {
-            Message::AlwaysFishingPointChecked(_) => (
-                &mut self.always_fishing_point_checkbox,
-                self.assemble.always_fishing_point_charism.borrow(),
-            ),
-            Message::CatchBetterFishChecked(_) => (
-                &mut self.catch_better_fish_checkbox,
-                self.assemble.catch_better_fish_charism.borrow(),
-            ),
-            Message::EasierToPickUpCheced(_) => (
-                &mut self.easier_to_pick_up_checkbox,
-                self.assemble.easier_to_pick_up_charism.borrow(),
-            ),
-            Message::GifitTraitQuickUpgradeChecked(_) => (
-                &mut self.gifit_trait_quick_upgrade_checkbox,
-                self.assemble.gifit_trait_quick_upgrade_charism.borrow(),
-            ),
-            Message::FreeStoreExchangeChecked(_) => (
-                &mut self.free_store_exchange_checkbox,
-                self.assemble.free_store_exchange_charism.borrow(),
-            ),
-            Message::AlwaysHeroRaityTraitChecked(_) => (
-                &mut self.always_hero_raity_trait_checkbox,
-                self.assemble.always_hero_raity_trait_charism.borrow(),
-            ),
-            _ => {
-                return;
-            }
-        };
-        // change checkbox to value
-        *checkbox_ptr = value;
-        if value {
-            // checked, apply
-            match charism.apply() {
-                Ok(_) => {
-                    log::info!("Pannel: {} apply success.", charism.name);
-                    self.toasts.push(Toast {
-                        title: "Apply".into(),
-                        body: "success".into(),
-                        status: Status::Success,
-                    });
-                }
-                Err(err) => {
-                    log::error!("Pannel: {} apply failed!", err);
-                    *checkbox_ptr = false;
-                    self.toasts.push(Toast {
-                        title: "Apply".into(),
-                        body: "failed".into(),
-                        status: Status::Danger,
-                    });
-                }
-            }
+        // enable or disable all charisms of the group
+        let toggle_context = if all_checked {
+            "Disable All"
         } else {
-            // uncheck, rollback
-            match charism.rollback(true) {
-                Ok(_) => {
-                    log::info!("Pannel: {} rollback success.", charism.name);
-                    self.toasts.push(Toast {
-                        title: "RollBack".into(),
-                        body: "success".into(),
-                        status: Status::Success,
-                    });
-                }
-                Err(err) => {
-                    log::error!("Pannel: {} rollback failed!", err);
-                    self.toasts.push(Toast {
-                        title: "RollBack".into(),
-                        body: "failed".into(),
-                        status: Status::Danger,
-                    });
-                }
+            "Enable All"
+        };
+        let group_toggle = button(
+            Text::new(language.tr(toggle_context))
+                .font(YY_FONT)
+                .size(TIP_SIZE),
+        )
+        .on_press(Message::GroupChecked(group.to_string(), !all_checked));
+
+        let header = row![fold_button, group_text, group_toggle]
+            .spacing(12)
+            .align_items(iced::Alignment::Center);
+
+        let mut group_column = column![header].spacing(12);
+        if !collapsed {
+            for index in indexes {
+                let charism = charisms[index].borrow();
+                group_column = group_column.push(self.charism_row(index, &charism));
             }
         }
+        group_column.into()
     }
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     FloderPickPressed,
-    AlwaysFishingPointChecked(bool),
-    CatchBetterFishChecked(bool),
-    EasierToPickUpCheced(bool),
-    GifitTraitQuickUpgradeChecked(bool),
-    FreeStoreExchangeChecked(bool),
-    AlwaysHeroRaityTraitChecked(bool),
+    InstallationSelected(String),
+    InstallationRemoved,
+    PresetSelected(String),
+    PresetNameChanged(String),
+    PresetSaved,
+    CharismChecked(usize, bool),
+    GroupCollapsed(String),
+    GroupChecked(String, bool),
+    SearchChanged(String),
+    FilterSelected(CharismFilter),
+    PreviewOpened(usize),
+    HistoryOpened,
+    HistoryFiltered(Option<Status>),
+    HistoryCopied(usize),
+    LogsOpened,
+    LogsPolled,
+    LogLevelSelected(Level),
+    LogSearchChanged(String),
+    VerbositySelected(Verbosity),
+    FileChanged(PathBuf),
+    WatchTicked,
+    Reapplied(Vec<usize>),
+    StatesRefreshed,
+    SnapshotsOpened,
+    SnapshotTaken,
+    SnapshotsPruned,
+    SnapshotCompared(String),
+    SnapshotDiffOpened,
+    SnapshotRestored(String, Option<String>),
+    VanillaRequested,
+    VanillaRestored,
+    VersionVerified(usize),
+    ParamChanged(usize, f64),
+    ParamReleased(usize),
+    ParamChosen(usize, f64),
+    PaneClosed,
     RollbackChecked(bool),
+    LanguageSelected(Language),
+    Undo,
+    Redo,
+    ShortcutsOpened,
+    ThemeSelected(String),
     ToastClose(usize),
+    ToastToggled(usize),
+    ToastAction(usize, Box<Message>),
     Event(Event),
     SourceLoaded(Result<(), font::Error>),
 }
 
 impl Application for Pannel<'_> {
     type Message = Message;
-    type Flags = ();
+    type Flags = Config;
     type Executor = executor::Default;
     type Theme = Theme;
 
-    fn new(_flags: Self::Flags) -> (Self, Command<Message>) {
+    fn new(config: Self::Flags) -> (Self, Command<Message>) {
+        let mut pannel = Self {
+            config,
+            ..Self::default()
+        };
+        // the installation of the last session
+        pannel.use_installation(pannel.config.installation.clone());
+        pannel.keymap = Keymap::load();
+        pannel.themes = themes::load();
+        if let Some(theme) = pannel
+            .themes
+            .iter()
+            .find(|theme| theme.name == pannel.config.theme)
+        {
+            pannel.theme = theme.clone();
+        }
+        // the level of command line or environment wins over the saved one
+        if !logger::is_overridden() && pannel.config.verbosity != Verbosity::Default {
+            logger::set_verbosity(pannel.config.verbosity);
+        }
         (
-            Self::default(),
+            pannel,
             Command::batch(vec![
                 font::load(include_bytes!("../fonts/icons.ttf").as_slice())
                     .map(Message::SourceLoaded),
@@ -184,18 +1616,23 @@ impl Application for Pannel<'_> {
     }
 
     fn subscription(&self) -> Subscription<Self::Message> {
-        subscription::events().map(Message::Event)
+        let mut subscriptions = vec![subscription::events().map(Message::Event)];
+        // tail the log file while the logs pane is shown
+        if matches!(self.pane, Pane::Logs) {
+            subscriptions.push(time::every(LOG_INTERVAL).map(|_| Message::LogsPolled));
+        }
+        if !self.hades_path().is_empty() {
+            subscriptions
+                .push(watcher::watch(self.hades_path().to_string()).map(Message::FileChanged));
+        }
+        if self.last_change.is_some() {
+            subscriptions.push(time::every(WATCH_DEBOUNCE).map(|_| Message::WatchTicked));
+        }
+        Subscription::batch(subscriptions)
     }
 
     fn theme(&self) -> Self::Theme {
-        let palette = Palette {
-            background: Color::from_rgb(32 as f32 / 255.0, 34 as f32 / 255.0, 37 as f32 / 255.0),
-            text: Color::from_rgb(0.90, 0.90, 0.90),
-            primary: RED_COLOR,
-            success: Color::from_rgb(18 as f32 / 255.0, 102 as f32 / 255.0, 79 as f32 / 255.0),
-            danger: Color::from_rgb(195 as f32 / 255.0, 66 as f32 / 255.0, 63 as f32 / 255.0),
-        };
-        Theme::custom(palette)
+        self.theme.iced()
     }
 
     fn update(&mut self, message: Message) -> Command<Message> {
@@ -214,74 +1651,376 @@ impl Application for Pannel<'_> {
             })) => {
                 return widget::focus_next();
             }
+            Message::Event(Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            })) => {
+                if let Some(shortcut) = self.keymap.find(key_code, modifiers) {
+                    return self.shortcut(shortcut);
+                }
+            }
+            Message::Event(Event::Window(window::Event::Resized { width, height })) => {
+                // a minimized window is resized to zero
+                if width > 0 && height > 0 {
+                    self.config.window.width = width;
+                    self.config.window.height = height;
+                }
+            }
+            Message::Event(Event::Window(window::Event::Moved { x, y })) => {
+                if x > MINIMIZED_POSITION && y > MINIMIZED_POSITION {
+                    self.config.window.x = Some(x);
+                    self.config.window.y = Some(y);
+                }
+            }
+            Message::Event(Event::Window(window::Event::CloseRequested)) => {
+                if let Err(err) = self.config.save() {
+                    log::error!("Pannel: save config failed, {}", err);
+                }
+                return window::close();
+            }
+            Message::Event(Event::Window(window::Event::FileDropped(path))) => {
+                self.file_dropped(path);
+            }
             Message::Event(_) => {}
             Message::FloderPickPressed => {
                 if let Some(floder_path) = FileDialog::new().pick_folder() {
-                    let path = floder_path.display().to_string();
-                    self.hades_path = path;
-
-                    //  create a new assemble, otherwise the backup_files is the same as before
-                    self.assemble = Assemble::new();
-                    self.assemble
-                        .set_hades_path(floder_path.display().to_string());
-                    self.assemble.assemble_all();
-                    log::info!("Change Path to {}", self.assemble.hades_path)
-                }
-            }
-            Message::ToastClose(_index) => {
-                // a little problem in remove(index)
-                // self.toasts.remove(_index);
-                self.toasts.clear();
-            }
-            Message::AlwaysFishingPointChecked(value)
-            | Message::CatchBetterFishChecked(value)
-            | Message::EasierToPickUpCheced(value)
-            | Message::GifitTraitQuickUpgradeChecked(value)
-            | Message::FreeStoreExchangeChecked(value)
-            | Message::AlwaysHeroRaityTraitChecked(value) => {
+                    self.add_installation(floder_path.display().to_string());
+                }
+            }
+            Message::InstallationSelected(name) => {
+                if name != self.config.installation {
+                    self.use_installation(name);
+                }
+            }
+            Message::InstallationRemoved => {
+                let name = self.config.installation.clone();
+                self.config.remove_installation(&name);
+                log::info!("Pannel: installation {} removed.", name);
+                self.use_installation(self.config.installation.clone());
+            }
+            Message::PresetSelected(name) => {
                 // check hades_path
-                if self.hades_path == "" {
-                    log::warn!("Pick Floder First!");
-                    let toast = Toast {
-                        title: "Tips".into(),
-                        body: "Pick Floder First".into(),
-                        status: Status::Primary,
-                    };
-                    self.toasts.push(toast);
+                if !self.check_hades_path() {
+                    return Command::none();
+                }
+                self.apply_preset(&name);
+            }
+            Message::PresetNameChanged(preset_name) => {
+                self.preset_name = preset_name;
+            }
+            Message::PresetSaved => {
+                let name = self.preset_name.trim().to_string();
+                let enabled = self.checked_names();
+                let installation = match self.config.current_installation_mut() {
+                    Some(installation) if !name.is_empty() => installation,
+                    _ => return Command::none(),
+                };
+                installation.presets.insert(name.clone(), enabled);
+                self.save_config();
+                self.preset_name.clear();
+                let language = self.config.language;
+                self.push_toast(Toast::new(
+                    language.tr("Save preset"),
+                    format!("{} {}", name, language.tr("success")),
+                    Status::Success,
+                ));
+            }
+            Message::LanguageSelected(language) => {
+                self.config.language = language;
+                if let Err(err) = self.config.save() {
+                    log::error!("Pannel: save config failed, {}", err);
+                }
+            }
+            Message::ThemeSelected(name) => {
+                if let Some(theme) = self.themes.iter().find(|theme| theme.name == name) {
+                    self.theme = theme.clone();
+                    self.config.theme = name;
+                    if let Err(err) = self.config.save() {
+                        log::error!("Pannel: save config failed, {}", err);
+                    }
+                }
+            }
+            Message::ToastClose(id) => {
+                self.toasts.retain(|toast| toast.id != id);
+            }
+            Message::ToastAction(id, message) => {
+                self.toasts.retain(|toast| toast.id != id);
+                return self.update(*message);
+            }
+            Message::ToastToggled(id) => {
+                if let Some(toast) = self.toasts.iter_mut().find(|toast| toast.id == id) {
+                    toast.expanded = !toast.expanded;
+                }
+            }
+            Message::CharismChecked(index, value) => {
+                // check hades_path
+                if !self.check_hades_path() {
+                    return Command::none();
+                }
+                if self.checkbox_selector(value, index) {
+                    self.record(vec![(index, value)]);
+                }
+            }
+            Message::GroupCollapsed(group) => {
+                match self
+                    .collapsed_groups
+                    .iter()
+                    .position(|value| *value == group)
+                {
+                    Some(position) => {
+                        self.collapsed_groups.remove(position);
+                    }
+                    None => self.collapsed_groups.push(group),
+                }
+            }
+            Message::GroupChecked(group, value) => {
+                // check hades_path
+                if !self.check_hades_path() {
+                    return Command::none();
+                }
+                let mut changes = Vec::new();
+                for index in self.visible_indexes(&group) {
+                    if self.charism_checkboxes[index] != value
+                        && self.checkbox_selector(value, index)
+                    {
+                        changes.push((index, value));
+                    }
+                }
+                self.record(changes);
+            }
+            Message::Undo => {
+                if let Some(changes) = self.undo_stack.pop() {
+                    for &(index, value) in changes.iter().rev() {
+                        self.checkbox_selector(!value, index);
+                    }
+                    self.redo_stack.push(changes);
+                }
+            }
+            Message::Redo => {
+                if let Some(changes) = self.redo_stack.pop() {
+                    for &(index, value) in changes.iter() {
+                        self.checkbox_selector(value, index);
+                    }
+                    self.undo_stack.push(changes);
+                }
+            }
+            Message::ShortcutsOpened => {
+                self.pane = Pane::Shortcuts;
+            }
+            Message::SearchChanged(search) => {
+                self.search = search;
+            }
+            Message::FilterSelected(charism_filter) => {
+                self.charism_filter = charism_filter;
+            }
+            Message::PreviewOpened(index) => {
+                // check hades_path
+                if !self.check_hades_path() {
+                    return Command::none();
+                }
+                self.open_preview(index);
+            }
+            Message::HistoryOpened => {
+                self.pane = Pane::History;
+            }
+            Message::HistoryFiltered(history_filter) => {
+                self.history_filter = history_filter;
+            }
+            Message::HistoryCopied(index) => {
+                if let Some((time, toast)) = self.history.get(index) {
+                    let mut contents = format!(
+                        "[{}][{}] {}: {}",
+                        time.format("%Y-%m-%d %H:%M:%S"),
+                        toast.status,
+                        toast.title,
+                        toast.body
+                    );
+                    if let Some(detail) = &toast.detail {
+                        contents.push('\n');
+                        contents.push_str(detail);
+                    }
+                    return clipboard::write(contents);
+                }
+            }
+            Message::LogsOpened => {
+                self.pane = Pane::Logs;
+                self.poll_logs();
+            }
+            Message::LogsPolled => {
+                self.poll_logs();
+            }
+            Message::LogLevelSelected(level) => {
+                self.log_level = Some(level);
+            }
+            Message::LogSearchChanged(log_search) => {
+                self.log_search = log_search;
+            }
+            Message::VerbositySelected(verbosity) => {
+                self.config.verbosity = verbosity;
+                logger::set_verbosity(verbosity);
+                if let Err(err) = self.config.save() {
+                    log::error!("Pannel: save config failed, {}", err);
+                }
+            }
+            Message::FileChanged(path) => {
+                self.changed_files.insert(path);
+                self.last_change = Some(Instant::now());
+            }
+            Message::WatchTicked => {
+                if let Some(last_change) = self.last_change {
+                    if last_change.elapsed() >= WATCH_DEBOUNCE {
+                        self.last_change = None;
+                        self.check_changed_files();
+                    }
+                }
+            }
+            Message::Reapplied(indexes) => {
+                // check hades_path
+                if !self.check_hades_path() {
+                    return Command::none();
+                }
+                let mut changes = Vec::new();
+                for index in indexes {
+                    if self.checkbox_selector(true, index) {
+                        changes.push((index, true));
+                    }
+                }
+                self.record(changes);
+            }
+            Message::StatesRefreshed => {
+                self.refresh_states();
+            }
+            Message::SnapshotsOpened => {
+                self.pane = Pane::Snapshots;
+                self.snapshots = snapshot::list(Some(self.hades_path()));
+            }
+            Message::SnapshotTaken => {
+                // check hades_path
+                if !self.check_hades_path() {
+                    return Command::none();
+                }
+                let language = self.config.language;
+                let toast = match snapshot::create(self.hades_path(), "manual") {
+                    Ok(value) => Toast::new(
+                        language.tr("Take snapshot"),
+                        format!("{} {}", value.id, language.tr("success")),
+                        Status::Success,
+                    ),
+                    Err(err) => {
+                        log::error!("Pannel: snapshot `{}` failed, {}", self.hades_path(), err);
+                        Toast::new(
+                            language.tr("Take snapshot"),
+                            language.tr("failed").to_string(),
+                            Status::Danger,
+                        )
+                        .detail(error_detail(language, &err))
+                        .action(language.tr("Show log"), Message::LogsOpened)
+                    }
+                };
+                self.push_toast(toast);
+                self.prune_snapshots();
+            }
+            Message::SnapshotsPruned => {
+                let language = self.config.language;
+                let pruned = self.prune_snapshots();
+                self.push_toast(Toast::new(
+                    language.tr("Prune"),
+                    format!("{} {}", pruned, language.tr("snapshots pruned")),
+                    Status::Success,
+                ));
+            }
+            Message::SnapshotCompared(id) => {
+                match self
+                    .compared_snapshots
+                    .iter()
+                    .position(|value| *value == id)
+                {
+                    Some(position) => {
+                        self.compared_snapshots.remove(position);
+                    }
+                    None => {
+                        // two at most, the earliest chosen is replaced
+                        if self.compared_snapshots.len() == 2 {
+                            self.compared_snapshots.remove(0);
+                        }
+                        self.compared_snapshots.push(id);
+                    }
+                }
+            }
+            Message::SnapshotDiffOpened => {
+                self.open_snapshot_diff();
+            }
+            Message::VersionVerified(index) => {
+                let charisms = self.assemble.charisms();
+                if let Some(charism) = charisms.get(index) {
+                    let name = charism.borrow().name.to_string();
+                    self.config.add_verified(&name, &self.assemble.fingerprint);
+                    log::info!(
+                        "Pannel: {} verified on {}.",
+                        name,
+                        self.assemble.fingerprint
+                    );
+                    self.save_config();
+                }
+            }
+            Message::ParamChanged(index, value) => {
+                self.set_param(index, value);
+            }
+            Message::ParamReleased(index) => {
+                self.save_param(index);
+            }
+            Message::ParamChosen(index, value) => {
+                self.set_param(index, value);
+                self.save_param(index);
+            }
+            Message::VanillaRequested => {
+                // check hades_path
+                if !self.check_hades_path() {
+                    return Command::none();
+                }
+                // nothing is touched until the toast is confirmed
+                let language = self.config.language;
+                let toast = Toast::new(
+                    language.tr("Restore vanilla"),
+                    language
+                        .tr("Restore every backed up file and remove the files created by mods?")
+                        .to_string(),
+                    Status::Primary,
+                )
+                .action(language.tr("Restore"), Message::VanillaRestored);
+                self.push_toast(toast);
+            }
+            Message::VanillaRestored => {
+                // check hades_path
+                if !self.check_hades_path() {
+                    return Command::none();
+                }
+                self.restore_vanilla();
+            }
+            Message::SnapshotRestored(id, file) => {
+                // check hades_path
+                if !self.check_hades_path() {
                     return Command::none();
                 }
-                self.checkbox_selector(value, message);
+                self.restore_snapshot(id, file);
+                if let Pane::SnapshotDiff(..) = self.pane {
+                    self.pane = Pane::Snapshots;
+                }
+            }
+            Message::PaneClosed => {
+                self.pane = Pane::Charisms;
             }
             Message::RollbackChecked(value) => {
                 self.roolback_checkbox = value;
                 // rollback the checkbox with a value of ture
                 if value {
-                    if self.always_fishing_point_checkbox {
-                        self.checkbox_selector(false, Message::AlwaysFishingPointChecked(false))
+                    let mut changes = Vec::new();
+                    for index in 0..self.charism_checkboxes.len() {
+                        if self.charism_checkboxes[index] && self.checkbox_selector(false, index) {
+                            changes.push((index, false));
+                        }
                     }
-
-                    if self.catch_better_fish_checkbox {
-                        self.checkbox_selector(false, Message::CatchBetterFishChecked(false));
-                    };
-
-                    if self.easier_to_pick_up_checkbox {
-                        self.checkbox_selector(false, Message::EasierToPickUpCheced(false));
-                    };
-
-                    if self.gifit_trait_quick_upgrade_checkbox {
-                        self.checkbox_selector(
-                            false,
-                            Message::GifitTraitQuickUpgradeChecked(false),
-                        );
-                    };
-
-                    if self.free_store_exchange_checkbox {
-                        self.checkbox_selector(false, Message::FreeStoreExchangeChecked(false));
-                    };
-
-                    if self.always_hero_raity_trait_checkbox {
-                        self.checkbox_selector(false, Message::AlwaysHeroRaityTraitChecked(false));
-                    };
+                    self.record(changes);
                 }
             }
         }
@@ -289,186 +2028,232 @@ impl Application for Pannel<'_> {
     }
 
     fn view(&self) -> Element<Message> {
+        let language = self.config.language;
+
         // let handle = image::Handle::from_path(format!(
         //     "{}/resources/hades-icon.jpg",
         //     env!("CARGO_MANIFEST_DIR")
         // ));
 
-        // image for banner
-        let image =
-            Image::<image::Handle>::new("resources/zagreus-icon-2.jpg").width(Length::Fixed(500.0));
+        let (width, height) = (self.config.window.width, self.config.window.height);
+        // image for banner, scaled down on narrow windows
+        let banner_width = BANNER_WIDTH.min(width.saturating_sub(100));
+        let image = Image::new(image::Handle::from_memory(BANNER_IMAGE))
+            .width(Length::Fixed(banner_width as f32));
 
         // text "Path"
-        let path_text = text("Path")
+        let path_text = text(language.tr("Path"))
             .size(FONT_SIZE)
             .font(YY_FONT)
-            .style(ORANGE_COLOR);
+            .style(self.theme.accent);
 
         // changes button context according to the value of hades_path
-        let mut context = "Pick Floder";
-        if self.hades_path != "" {
-            context = &self.hades_path;
+        let mut context = language.tr("Pick Floder");
+        if !self.hades_path().is_empty() {
+            context = self.hades_path();
         }
 
         // pick floder button
         let floder_picker = button(Text::new(context).font(YY_FONT).size(TIP_SIZE))
             .on_press(Message::FloderPickPressed);
 
-        // combine the path and floder_picker in a row
-        let line = row![path_text, floder_picker]
-            .spacing(24)
-            .align_items(iced::Alignment::Center);
-
-        // "Always Fishing Point",
-        let always_fishing_point_checkbox = checkbox(
-            "",
-            self.always_fishing_point_checkbox,
-            Message::AlwaysFishingPointChecked,
+        // switch between the registered installations
+        let installation_names: Vec<String> = self
+            .config
+            .installations
+            .iter()
+            .map(|installation| installation.name.clone())
+            .collect();
+        let installation_picker = pick_list(
+            installation_names,
+            self.config
+                .current_installation()
+                .map(|installation| installation.name.clone()),
+            Message::InstallationSelected,
         )
+        .placeholder(language.tr("Installation"))
+        .text_size(TIP_SIZE)
         .font(YY_FONT);
-
-        let always_fishing_point_tip = Tooltip::new(
-            Text::new(self.assemble.always_fishing_point_charism.borrow().name)
+        let mut installation_remover = button(
+            Text::new(language.tr("Remove"))
                 .font(YY_FONT)
-                .size(FONT_SIZE)
-                .style(ORANGE_COLOR),
-            self.assemble
-                .always_fishing_point_charism
-                .borrow()
-                .description,
-            TIP_POSITION,
+                .size(TIP_SIZE),
         )
-        // .gap(10)
-        .font(YY_FONT)
-        // .padding(10)
-        .size(TIP_SIZE);
-        let always_fishing_point = row![always_fishing_point_checkbox, always_fishing_point_tip];
+        .style(theme::Button::Destructive);
+        if self.config.current_installation().is_some() {
+            installation_remover = installation_remover.on_press(Message::InstallationRemoved);
+        }
 
-        // "Catch Better Fish",
-        let catch_better_fish_checkbox = checkbox(
-            "",
-            self.catch_better_fish_checkbox,
-            Message::CatchBetterFishChecked,
-        )
-        .font(YY_FONT);
-        let catch_better_fish_tip = Tooltip::new(
-            Text::new(self.assemble.catch_better_fish_charism.borrow().name)
-                .font(YY_FONT)
-                .size(FONT_SIZE)
-                .style(ORANGE_COLOR),
-            self.assemble.catch_better_fish_charism.borrow().description,
-            TIP_POSITION,
-        )
-        // .gap(10)
-        .font(YY_FONT)
-        // .padding(10)
-        .size(TIP_SIZE);
-        let catch_better_fish = row![catch_better_fish_checkbox, catch_better_fish_tip];
+        // narrow windows, such as the default one, split the rows in two
+        let narrow = width < NARROW_WIDTH;
+        // combine the path and floder_picker in a row, the path goes below
+        // on narrow windows
+        let line = split_row(
+            narrow,
+            vec![
+                path_text.into(),
+                installation_picker.into(),
+                installation_remover.into(),
+            ],
+            vec![floder_picker.into()],
+        );
 
-        // "Easier To Pickup"
-        let easier_to_pick_up_checkbox = checkbox(
-            "",
-            self.easier_to_pick_up_checkbox,
-            Message::EasierToPickUpCheced,
-        )
-        .font(YY_FONT);
-        let easier_to_pick_up_tip = Tooltip::new(
-            Text::new(self.assemble.easier_to_pick_up_charism.borrow().name)
+        // presets of the installation
+        let presets_text = text(language.tr("Presets"))
+            .size(FONT_SIZE)
+            .font(YY_FONT)
+            .style(self.theme.accent);
+        let preset_names: Vec<String> = self
+            .config
+            .current_installation()
+            .map(|installation| installation.presets.keys().cloned().collect())
+            .unwrap_or_default();
+        let preset_picker = pick_list(preset_names, None::<String>, Message::PresetSelected)
+            .placeholder(language.tr("Presets"))
+            .text_size(TIP_SIZE)
+            .font(YY_FONT);
+        let preset_input = text_input(language.tr("Preset name"), &self.preset_name)
+            .on_input(Message::PresetNameChanged)
+            .on_submit(Message::PresetSaved)
+            .size(TIP_SIZE)
+            .font(YY_FONT)
+            .width(Length::Fixed(160.0));
+        let preset_saver = button(
+            Text::new(language.tr("Save preset"))
                 .font(YY_FONT)
-                .size(FONT_SIZE)
-                .size(FONT_SIZE)
-                .style(ORANGE_COLOR),
-            self.assemble.easier_to_pick_up_charism.borrow().description,
-            TIP_POSITION,
+                .size(TIP_SIZE),
         )
-        // .gap(10)
-        .font(YY_FONT)
-        // .padding(10)
-        .size(TIP_SIZE);
-        let easier_to_pick_up = row![easier_to_pick_up_checkbox, easier_to_pick_up_tip];
+        .on_press(Message::PresetSaved);
+        let presets_line = split_row(
+            narrow,
+            vec![presets_text.into(), preset_picker.into()],
+            vec![preset_input.into(), preset_saver.into()],
+        );
 
-        // "GifitTrait Quick Upgrade"
-        let gifit_trait_quick_upgrade_checkbox = checkbox(
-            "",
-            self.gifit_trait_quick_upgrade_checkbox,
-            Message::GifitTraitQuickUpgradeChecked,
-        )
-        .font(YY_FONT);
-        let gifit_trait_quick_upgrade_tip = Tooltip::new(
-            Text::new(
-                self.assemble
-                    .gifit_trait_quick_upgrade_charism
-                    .borrow()
-                    .name,
-            )
-            .font(YY_FONT)
+        // language switch
+        let language_text = text(language.tr("Language"))
             .size(FONT_SIZE)
-            .style(ORANGE_COLOR),
-            self.assemble
-                .gifit_trait_quick_upgrade_charism
-                .borrow()
-                .description,
-            TIP_POSITION,
+            .font(YY_FONT)
+            .style(self.theme.accent);
+        let language_picker = pick_list(
+            &Language::ALL[..],
+            Some(language),
+            Message::LanguageSelected,
         )
-        // .gap(10)
         .font(YY_FONT)
-        // .padding(10)
-        .size(TIP_SIZE);
-        let gifit_trait_quick_upgrade = row![
-            gifit_trait_quick_upgrade_checkbox,
-            gifit_trait_quick_upgrade_tip
-        ];
-
-        // "Free Store Exchange"
-        let free_store_exchange_checkbox = checkbox(
-            "",
-            self.free_store_exchange_checkbox,
-            Message::FreeStoreExchangeChecked,
+        .text_size(TIP_SIZE);
+        // theme switch
+        let theme_picker = pick_list(
+            self.themes
+                .iter()
+                .map(|theme| theme.name.clone())
+                .collect::<Vec<_>>(),
+            Some(self.theme.name.clone()),
+            Message::ThemeSelected,
         )
-        .font(YY_FONT);
-        let free_store_exchange_tip = Tooltip::new(
-            Text::new(self.assemble.free_store_exchange_charism.borrow().name)
-                .font(YY_FONT)
-                .size(FONT_SIZE)
-                .style(ORANGE_COLOR),
-            self.assemble
-                .free_store_exchange_charism
-                .borrow()
-                .description,
-            TIP_POSITION,
-        )
-        // .gap(10)
         .font(YY_FONT)
-        // .padding(10)
-        .size(TIP_SIZE);
-        let free_store_exchange = row![free_store_exchange_checkbox, free_store_exchange_tip];
-
-        // "Always Hero RaityTrait"
-        let always_hero_raity_trait_checkbox = checkbox(
-            "",
-            self.always_hero_raity_trait_checkbox,
-            Message::AlwaysHeroRaityTraitChecked,
+        .text_size(TIP_SIZE);
+        // open the notification history
+        let history_button = button(
+            Text::new(language.tr("History"))
+                .font(YY_FONT)
+                .size(TIP_SIZE),
         )
-        .font(YY_FONT);
-        let always_hero_raity_trait_tip = Tooltip::new(
-            Text::new(self.assemble.always_hero_raity_trait_charism.borrow().name)
+        .on_press(Message::HistoryOpened);
+        // open the log viewer
+        let logs_button = button(Text::new(language.tr("Logs")).font(YY_FONT).size(TIP_SIZE))
+            .on_press(Message::LogsOpened);
+        // open the snapshots of the Scripts folder
+        let snapshots_button = button(
+            Text::new(language.tr("Snapshots"))
                 .font(YY_FONT)
-                .size(FONT_SIZE)
-                .style(ORANGE_COLOR),
-            self.assemble
-                .always_hero_raity_trait_charism
-                .borrow()
-                .description,
-            TIP_POSITION,
+                .size(TIP_SIZE),
         )
-        // .gap(10)
-        .font(YY_FONT)
-        // .padding(10)
-        .size(TIP_SIZE);
-        let always_hero_raity_trait = row![
-            always_hero_raity_trait_checkbox,
-            always_hero_raity_trait_tip
-        ];
+        .on_press(Message::SnapshotsOpened);
+        // open the cheat sheet of shortcuts
+        let shortcuts_button =
+            button(Text::new("?").font(YY_FONT).size(TIP_SIZE)).on_press(Message::ShortcutsOpened);
+        // the settings, then the panes
+        let language_line = split_row(
+            narrow,
+            vec![
+                language_text.into(),
+                language_picker.into(),
+                theme_picker.into(),
+            ],
+            vec![
+                history_button.into(),
+                logs_button.into(),
+                snapshots_button.into(),
+                shortcuts_button.into(),
+            ],
+        );
+
+        // search input and filter of charisms
+        let search_placeholder = format!(
+            "{} ({})",
+            language.tr("Search"),
+            self.keymap.binding(Shortcut::Search)
+        );
+        let search_input = text_input(&search_placeholder, &self.search)
+            .id(text_input::Id::new(SEARCH_INPUT))
+            .on_input(Message::SearchChanged)
+            .font(YY_FONT)
+            .size(TIP_SIZE)
+            .padding(5)
+            .width(Length::Fixed(240.0));
+        let mut search_line = row![search_input]
+            .spacing(12)
+            .align_items(iced::Alignment::Center);
+        for charism_filter in CharismFilter::ALL {
+            let style = if charism_filter == self.charism_filter {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            };
+            search_line = search_line.push(
+                button(
+                    Text::new(language.tr(charism_filter.label()))
+                        .font(YY_FONT)
+                        .size(TIP_SIZE),
+                )
+                .style(style)
+                .on_press(Message::FilterSelected(charism_filter)),
+            );
+        }
+
+        // charisms grouped by `Charism.group`, empty groups are hidden,
+        // wide windows list them in two columns
+        let wide = width >= WIDE_WIDTH;
+        let mut sides: [Vec<Element<Message>>; 2] = [Vec::new(), Vec::new()];
+        let mut group_count = 0;
+        for group in self.assemble.groups() {
+            if !self.visible_indexes(&group).is_empty() {
+                let side = if wide { group_count % 2 } else { 0 };
+                sides[side].push(self.group_column(&group));
+                group_count += 1;
+            }
+        }
+        if group_count == 0 {
+            sides[0].push(
+                text(language.tr("No mods found"))
+                    .size(TIP_SIZE)
+                    .font(YY_FONT)
+                    .into(),
+            );
+        }
+        let [left, right] = sides;
+        let charism_list: Element<Message> = if wide {
+            row![
+                Column::with_children(left).spacing(12).width(Length::Fill),
+                Column::with_children(right).spacing(12).width(Length::Fill)
+            ]
+            .spacing(24)
+            .into()
+        } else {
+            Column::with_children(left).spacing(12).into()
+        };
+        let charism_list = scrollable(charism_list).height(Length::Fill);
 
         let rollback_checkbox = checkbox("", self.roolback_checkbox, Message::RollbackChecked)
             .icon(checkbox::Icon {
@@ -479,26 +2264,52 @@ impl Application for Pannel<'_> {
                 shaping: text::Shaping::Basic,
             })
             .font(YY_FONT);
-        let rollback_text = Text::new("RollBack")
+        let rollback_text = Text::new(language.tr("RollBack"))
             .font(YY_FONT)
             .size(FONT_SIZE)
-            .style(GREEN_COLOR);
-        let rollback = row![rollback_checkbox, rollback_text];
+            .style(self.theme.highlight);
+        let vanilla_button = button(
+            Text::new(language.tr("Restore vanilla"))
+                .font(YY_FONT)
+                .size(TIP_SIZE),
+        )
+        .style(theme::Button::Destructive)
+        .on_press(Message::VanillaRequested);
+        let rollback = row![
+            rollback_checkbox,
+            rollback_text,
+            horizontal_space(Length::Fill),
+            vanilla_button
+        ]
+        .align_items(iced::Alignment::Center);
 
         // toast
 
-        let content = column![
-            image,
-            line,
-            always_fishing_point,
-            catch_better_fish,
-            easier_to_pick_up,
-            gifit_trait_quick_upgrade,
-            free_store_exchange,
-            always_hero_raity_trait,
-            rollback,
-        ]
-        .spacing(24);
+        let content: Element<Message> = match &self.pane {
+            Pane::Preview(index, file_diffs) => self.preview_view(*index, file_diffs),
+            Pane::History => self.history_view(),
+            Pane::Logs => self.logs_view(),
+            Pane::Shortcuts => self.shortcuts_view(),
+            Pane::Snapshots => self.snapshots_view(),
+            Pane::SnapshotDiff(title, restore_from, file_diffs) => {
+                self.snapshot_diff_view(title, restore_from.as_deref(), file_diffs)
+            }
+            Pane::Charisms => {
+                let mut charisms = column![].spacing(24).padding(24).height(Length::Fill);
+                // short windows leave the room to the list
+                if height >= BANNER_HEIGHT {
+                    charisms = charisms.push(container(image).width(Length::Fill).center_x());
+                }
+                charisms
+                    .push(line)
+                    .push(presets_line)
+                    .push(language_line)
+                    .push(search_line)
+                    .push(charism_list)
+                    .push(rollback)
+                    .into()
+            }
+        };
 
         let container = container(content)
             .width(Length::Fill)
@@ -508,8 +2319,16 @@ impl Application for Pannel<'_> {
             // .padding(24)
             ;
         // toast manager
-        toast::Manager::new(container, &self.toasts, Message::ToastClose)
-            .timeout(1)
-            .into()
+        toast::Manager::new(
+            container,
+            &self.toasts,
+            Message::ToastClose,
+            Message::ToastToggled,
+        )
+        // plain toasts go quickly, those with actions wait to be clicked
+        .timeout(1)
+        .action_timeout(Some(ACTION_TOAST_TIMEOUT))
+        .max_visible(MAX_TOASTS)
+        .into()
     }
 }
@@ -0,0 +1,195 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::config::Config;
+use crate::store;
+use crate::utils::move_path;
+
+// directory of uranus under the per-user directories
+const APP_DIR: &str = "Uranus";
+// directory which replaces both per-user directories, e.g. for a portable copy
+#[cfg(not(test))]
+const HOME_ENV: &str = "URANUS_HOME";
+// namespace of the backups of the installations in the store
+const BACKUP_NAMESPACE: &str = "Eden";
+// locations used before, relative to the working directory
+const LEGACY_BACKUP_HOME: &str = "./Eden";
+const LEGACY_CONFIG_FILE: &str = "uranus.toml";
+
+/// Directory of the executable, used when the per-user directories are unknown.
+fn exe_dir() -> PathBuf {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+// home of the tests, taking the place of `URANUS_HOME`
+#[cfg(test)]
+static TEST_HOME: OnceLock<PathBuf> = OnceLock::new();
+
+/// Keep the data of the tests in the home instead of the user's. It is set
+/// before any path is used and stays the same for the whole run.
+#[cfg(test)]
+pub fn set_home_for_tests(home: PathBuf) {
+    let current = TEST_HOME.get_or_init(|| home.clone());
+    assert_eq!(*current, home, "the home of the tests is set once");
+}
+
+/// `URANUS_HOME` if set.
+#[cfg(not(test))]
+fn home_dir() -> Option<PathBuf> {
+    env::var_os(HOME_ENV)
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The home of the tests, never the user's directories.
+#[cfg(test)]
+fn home_dir() -> Option<PathBuf> {
+    let home = TEST_HOME
+        .get()
+        .expect("set_home_for_tests before using the paths");
+    Some(home.clone())
+}
+
+/// Data of the user, the backups and logs, e.g. `%LOCALAPPDATA%\Uranus`.
+pub fn data_dir() -> PathBuf {
+    if let Some(home) = home_dir() {
+        return home;
+    }
+    match dirs::data_local_dir() {
+        Some(dir) => dir.join(APP_DIR),
+        None => exe_dir(),
+    }
+}
+
+/// Settings of the user, e.g. `%APPDATA%\Uranus`.
+pub fn config_dir() -> PathBuf {
+    if let Some(home) = home_dir() {
+        return home;
+    }
+    match dirs::config_dir() {
+        Some(dir) => dir.join(APP_DIR),
+        None => exe_dir(),
+    }
+}
+
+/// File of the panel choices.
+pub fn config_file() -> PathBuf {
+    config_dir().join("uranus.toml")
+}
+
+/// Optional "log4rs.yaml" replacing the built-in log configuration.
+pub fn log_config_file() -> PathBuf {
+    config_dir().join("log4rs.yaml")
+}
+
+/// Directory of the log files.
+pub fn log_dir() -> PathBuf {
+    data_dir().join("logs")
+}
+
+/// Root of the store and snapshots, `backup_root` of config or the data directory.
+pub fn backup_root() -> PathBuf {
+    static BACKUP_ROOT: OnceLock<PathBuf> = OnceLock::new();
+    BACKUP_ROOT
+        .get_or_init(|| match Config::load().backup_root {
+            Some(root) => PathBuf::from(root),
+            None => data_dir(),
+        })
+        .clone()
+}
+
+/// Key of the origin files backed up before the store, all installations
+/// shared it; a directory of copies before the store.
+pub fn legacy_backup_home() -> String {
+    data_dir().join("Eden").to_string_lossy().to_string()
+}
+
+/// Key of the origin files of the installation backed up before modifying,
+/// named by the hash of the canonical path of its Scripts folder, so the
+/// backups of two installations never mix.
+pub fn backup_home(hades_path: &str) -> String {
+    let canonical = fs::canonicalize(hades_path).map_or(hades_path.to_string(), |path| {
+        path.to_string_lossy().to_string()
+    });
+    let hash = store::hash_content(canonical.as_bytes());
+    let backup_home = Path::new(BACKUP_NAMESPACE)
+        .join(&hash[..16])
+        .to_string_lossy()
+        .to_string();
+    if let Err(err) = store::register_home(&backup_home, &canonical, &legacy_backup_home()) {
+        log::error!(
+            "Paths: register backup home of `{}` failed, {}",
+            canonical,
+            err
+        );
+    }
+    backup_home
+}
+
+/// Move the backups and config of the working directory to the per-user
+/// directories, the existing ones are kept, and the copied backups into the store.
+pub fn migrate() {
+    let moves = [
+        (
+            PathBuf::from(LEGACY_BACKUP_HOME),
+            PathBuf::from(legacy_backup_home()),
+        ),
+        (PathBuf::from(LEGACY_CONFIG_FILE), config_file()),
+    ];
+    for (legacy, target) in moves {
+        if !legacy.exists() || target.exists() {
+            continue;
+        }
+        match move_path(&legacy, &target) {
+            Ok(_) => log::info!(
+                "Paths: moved `{}` to `{}`.",
+                legacy.display(),
+                target.display()
+            ),
+            Err(err) => log::error!(
+                "Paths: move `{}` to `{}` failed, {}",
+                legacy.display(),
+                target.display(),
+                err
+            ),
+        }
+    }
+
+    let legacy_backup_home = legacy_backup_home();
+    match store::import_dir(&legacy_backup_home) {
+        Ok(0) => {}
+        Ok(count) => log::info!(
+            "Paths: moved {} backups of `{}` into the store.",
+            count,
+            legacy_backup_home
+        ),
+        Err(err) => log::error!(
+            "Paths: move backups of `{}` into the store failed, {}",
+            legacy_backup_home,
+            err
+        ),
+    }
+}
+
+/// The Scripts folder of the game from a dropped path, which may be the
+/// install folder, the `Content` or `Scripts` folder, or a file inside them.
+pub fn scripts_dir(path: &Path) -> Option<PathBuf> {
+    // the game is found from "Hades/x64/Hades.exe" by its second folder
+    for dir in path.ancestors().filter(|dir| dir.is_dir()).take(3) {
+        let name = dir.file_name().unwrap_or_default();
+        if name.eq_ignore_ascii_case("Scripts") {
+            return Some(dir.to_path_buf());
+        }
+        for candidate in [dir.join("Scripts"), dir.join("Content").join("Scripts")] {
+            if candidate.is_dir() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
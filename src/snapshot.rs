@@ -0,0 +1,344 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::UranusError;
+use crate::paths;
+use crate::store;
+use crate::utils::{read_from_file, write_to_file};
+
+const MANIFEST: &str = "snapshot.toml";
+const ID_FORMAT: &str = "%Y%m%d-%H%M%S";
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// The files of a Scripts folder at a point of time, their contents are kept
+/// in the store and the manifest in its own directory of "snapshots", named
+/// by the time it was taken.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub created: String,
+    // why it was taken, e.g. "picked" or "before restore"
+    pub label: String,
+    pub hades_path: String,
+    // hash of all the files, the same game files have the same fingerprint
+    pub fingerprint: String,
+    pub files: Vec<SnapshotFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    pub name: String,
+    // sha256 of the content
+    pub hash: String,
+}
+
+/// How many snapshots of each Scripts folder are kept, the latest one is
+/// never pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Retention {
+    pub keep: usize,
+    // snapshots older than it are pruned, none to keep any age
+    pub max_days: Option<i64>,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Retention {
+            keep: 10,
+            max_days: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl Snapshot {
+    fn dir(&self) -> PathBuf {
+        snapshots_dir().join(&self.id)
+    }
+
+    /// Path of the file in the Scripts folder.
+    pub fn current_path(&self, name: &str) -> String {
+        Path::new(&self.hades_path)
+            .join(name)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn file(&self, name: &str) -> Option<&SnapshotFile> {
+        self.files.iter().find(|file| file.name == name)
+    }
+
+    /// Content of the file in the snapshot, checked against its hash.
+    pub fn read(&self, name: &str) -> Result<String, UranusError> {
+        let content = self.read_bytes(name)?;
+        Ok(String::from_utf8_lossy(&content).to_string())
+    }
+
+    fn read_bytes(&self, name: &str) -> Result<Vec<u8>, UranusError> {
+        let file = self.file(name).ok_or(UranusError::BackupMissing {
+            path: format!("{}/{}", self.id, name),
+        })?;
+        store::get(&file.hash)
+    }
+}
+
+/// Directory of the snapshots.
+pub fn snapshots_dir() -> PathBuf {
+    paths::backup_root().join("snapshots")
+}
+
+fn fingerprint(files: &[SnapshotFile]) -> String {
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(format!("{}:{}\n", file.name, file.hash));
+    }
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+/// Files of the Scripts folder, sorted by name.
+fn scripts_files(hades_path: &str) -> Result<Vec<PathBuf>, UranusError> {
+    let entries = fs::read_dir(hades_path).map_err(|err| UranusError::io(hades_path, err))?;
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Hashes of the files in the Scripts folder.
+fn current_files(hades_path: &str) -> Result<Vec<SnapshotFile>, UranusError> {
+    let mut files = Vec::new();
+    for path in scripts_files(hades_path)? {
+        let content =
+            fs::read(&path).map_err(|err| UranusError::io(path.to_string_lossy(), err))?;
+        files.push(SnapshotFile {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            hash: store::hash_content(&content),
+        });
+    }
+    Ok(files)
+}
+
+/// Keep the files of the Scripts folder in a new snapshot.
+pub fn create(hades_path: &str, label: &str) -> Result<Snapshot, UranusError> {
+    let now = Local::now();
+    // snapshots taken in the same second are told apart by a suffix
+    let mut id = now.format(ID_FORMAT).to_string();
+    let mut suffix = 1;
+    while snapshots_dir().join(&id).exists() {
+        id = format!("{}-{}", now.format(ID_FORMAT), suffix);
+        suffix += 1;
+    }
+
+    let mut files = Vec::new();
+    for path in scripts_files(hades_path)? {
+        let content =
+            fs::read(&path).map_err(|err| UranusError::io(path.to_string_lossy(), err))?;
+        files.push(SnapshotFile {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            hash: store::put(&content)?,
+        });
+    }
+    let snapshot = Snapshot {
+        id,
+        created: now.format(TIME_FORMAT).to_string(),
+        label: label.to_string(),
+        hades_path: hades_path.to_string(),
+        fingerprint: fingerprint(&files),
+        files,
+    };
+
+    let dir = snapshot.dir();
+    fs::create_dir_all(&dir).map_err(|err| UranusError::io(dir.to_string_lossy(), err))?;
+    let manifest = snapshot.dir().join(MANIFEST).to_string_lossy().to_string();
+    let content = toml::to_string(&snapshot).map_err(|err| UranusError::BackupCorrupt {
+        path: manifest.clone(),
+        reason: err.to_string(),
+    })?;
+    write_to_file(&manifest, &content)?;
+
+    log::info!(
+        "Snapshot: {} of `{}` taken, {} files.",
+        snapshot.id,
+        hades_path,
+        snapshot.files.len()
+    );
+    Ok(snapshot)
+}
+
+/// Take a snapshot unless the files are the same as the latest snapshot of the folder.
+pub fn create_if_changed(hades_path: &str, label: &str) -> Result<Option<Snapshot>, UranusError> {
+    if let Some(latest) = list(Some(hades_path)).pop() {
+        if latest.files == current_files(hades_path)? {
+            return Ok(None);
+        }
+    }
+    create(hades_path, label).map(Some)
+}
+
+/// Snapshots of the Scripts folder or of all folders if none, the oldest first.
+pub fn list(hades_path: Option<&str>) -> Vec<Snapshot> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(snapshots_dir())
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_default();
+    dirs.sort();
+
+    let mut snapshots = Vec::new();
+    for dir in dirs.iter().filter(|dir| dir.join(MANIFEST).is_file()) {
+        let manifest = dir.join(MANIFEST).to_string_lossy().to_string();
+        let snapshot: Snapshot = match read_from_file(&manifest)
+            .map_err(|err| err.to_string())
+            .and_then(|content| toml::from_str(&content).map_err(|err| err.to_string()))
+        {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                log::warn!("Snapshot: load `{}` failed, {}", manifest, err);
+                continue;
+            }
+        };
+        if hades_path.map_or(true, |path| path == snapshot.hades_path) {
+            snapshots.push(snapshot);
+        }
+    }
+    snapshots
+}
+
+/// The snapshot of the id.
+pub fn find(id: &str) -> Result<Snapshot, UranusError> {
+    list(None)
+        .into_iter()
+        .find(|snapshot| snapshot.id == id)
+        .ok_or(UranusError::SnapshotNotFound { id: id.to_string() })
+}
+
+/// Files added, removed or modified from the old snapshot to the new one,
+/// sorted by name.
+pub fn compare(old: &Snapshot, new: &Snapshot) -> Vec<(String, FileChange)> {
+    compare_files(&old.files, &new.files)
+}
+
+/// Files changed in the Scripts folder since the snapshot.
+pub fn compare_current(snapshot: &Snapshot) -> Result<Vec<(String, FileChange)>, UranusError> {
+    Ok(compare_files(
+        &snapshot.files,
+        &current_files(&snapshot.hades_path)?,
+    ))
+}
+
+fn compare_files(old: &[SnapshotFile], new: &[SnapshotFile]) -> Vec<(String, FileChange)> {
+    let mut changes = BTreeMap::new();
+    for file in old {
+        match new.iter().find(|value| value.name == file.name) {
+            Some(value) if value.hash != file.hash => {
+                changes.insert(file.name.clone(), FileChange::Modified);
+            }
+            Some(_) => {}
+            None => {
+                changes.insert(file.name.clone(), FileChange::Removed);
+            }
+        }
+    }
+    for file in new {
+        if !old.iter().any(|value| value.name == file.name) {
+            changes.insert(file.name.clone(), FileChange::Added);
+        }
+    }
+    changes.into_iter().collect()
+}
+
+/// Restore the file, or all files if none, of the Scripts folder to the
+/// snapshot, returns the restored files. The folder is snapshotted before,
+/// so the restore can be undone.
+pub fn restore(snapshot: &Snapshot, file: Option<&str>) -> Result<Vec<String>, UranusError> {
+    let names: Vec<String> = match file {
+        Some(name) => vec![name.to_string()],
+        None => snapshot
+            .files
+            .iter()
+            .map(|file| file.name.clone())
+            .collect(),
+    };
+    // check all contents before touching the folder
+    let mut contents = Vec::new();
+    for name in &names {
+        contents.push(snapshot.read_bytes(name)?);
+    }
+    create_if_changed(&snapshot.hades_path, "before restore")?;
+
+    let mut restored = Vec::new();
+    for (name, content) in names.into_iter().zip(contents) {
+        let dest = snapshot.current_path(&name);
+        if fs::read(&dest).map_or(true, |current| current != content) {
+            fs::write(&dest, &content).map_err(|err| UranusError::io(&dest, err))?;
+            restored.push(name);
+        }
+    }
+    log::info!(
+        "Snapshot: {} restored to `{}`, {:?}.",
+        snapshot.id,
+        snapshot.hades_path,
+        restored
+    );
+    Ok(restored)
+}
+
+/// Remove the snapshots out of the retention and the contents no longer
+/// used, returns their ids.
+pub fn prune(retention: Retention) -> Result<Vec<String>, UranusError> {
+    let mut folders: BTreeMap<String, Vec<Snapshot>> = BTreeMap::new();
+    for snapshot in list(None) {
+        folders
+            .entry(snapshot.hades_path.clone())
+            .or_default()
+            .push(snapshot);
+    }
+    let oldest = retention
+        .max_days
+        .map(|days| (Local::now() - Duration::days(days)).naive_local());
+
+    let mut pruned = Vec::new();
+    for snapshots in folders.values() {
+        for (age, snapshot) in snapshots.iter().rev().enumerate() {
+            let expired = match (
+                oldest,
+                NaiveDateTime::parse_from_str(&snapshot.created, TIME_FORMAT),
+            ) {
+                (Some(oldest), Ok(created)) => created < oldest,
+                _ => false,
+            };
+            if age == 0 || (age < retention.keep && !expired) {
+                continue;
+            }
+            let dir = snapshot.dir();
+            fs::remove_dir_all(&dir).map_err(|err| UranusError::io(dir.to_string_lossy(), err))?;
+            pruned.push(snapshot.id.clone());
+        }
+    }
+    log::info!("Snapshot: pruned {:?}.", pruned);
+    if !pruned.is_empty() {
+        store::gc()?;
+    }
+    Ok(pruned)
+}
@@ -0,0 +1,448 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::UranusError;
+use crate::paths;
+use crate::snapshot;
+use crate::utils::{origin_path, read_from_file};
+
+const INDEX: &str = "index.toml";
+const OBJECTS_DIR: &str = "objects";
+
+/// Backups of the store, keyed by the path of the backup, e.g.
+/// "Eden/1a2b3c4d5e6f7a8b/TraitData.lua", to the hash of the content,
+/// the Scripts folders of the backup homes and the keys of the files
+/// which did not exist before the mods.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Index {
+    #[serde(default)]
+    backups: BTreeMap<String, String>,
+    #[serde(default)]
+    homes: BTreeMap<String, String>,
+    #[serde(default)]
+    created: BTreeSet<String>,
+}
+
+/// The index of the operation in progress, loaded once and written once when
+/// the operation ends, and whether it was changed.
+#[derive(Default)]
+struct Batch {
+    index: Option<Index>,
+    changed: bool,
+}
+
+thread_local! {
+    static BATCH: RefCell<Option<Batch>> = const { RefCell::new(None) };
+}
+
+/// Contents kept by their sha256 in "store": each content is compressed
+/// once into "objects", however many backups and snapshots have it, and
+/// "index.toml" maps the backups to their contents.
+pub fn store_dir() -> PathBuf {
+    paths::backup_root().join("store")
+}
+
+fn index_path() -> String {
+    store_dir().join(INDEX).to_string_lossy().to_string()
+}
+
+fn object_path(hash: &str) -> PathBuf {
+    // objects are spread by the first two digits
+    let prefix = hash.get(..2).unwrap_or(hash);
+    store_dir().join(OBJECTS_DIR).join(prefix).join(hash)
+}
+
+/// Hex of the sha256 of the content.
+pub fn hash_content(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Write the file through a temporary file, so it is never half written.
+fn write_atomic(path: &Path, content: &[u8]) -> Result<(), UranusError> {
+    let path_name = path.to_string_lossy().to_string();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| UranusError::io(&path_name, err))?;
+    }
+    let temp = path.with_extension("tmp");
+    fs::write(&temp, content)
+        .and_then(|_| fs::rename(&temp, path))
+        .map_err(|err| UranusError::io(&path_name, err))
+}
+
+/// Keep the content in the store, returns its hash.
+pub fn put(content: &[u8]) -> Result<String, UranusError> {
+    let hash = hash_content(content);
+    let path = object_path(&hash);
+    if path.is_file() {
+        return Ok(hash);
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let compressed = encoder
+        .write_all(content)
+        .and_then(|_| encoder.finish())
+        .map_err(|err| UranusError::io(path.to_string_lossy(), err))?;
+    write_atomic(&path, &compressed)?;
+    Ok(hash)
+}
+
+pub fn contains(hash: &str) -> bool {
+    object_path(hash).is_file()
+}
+
+/// Content of the hash, checked against the hash.
+pub fn get(hash: &str) -> Result<Vec<u8>, UranusError> {
+    let path = object_path(hash);
+    let path_name = path.to_string_lossy().to_string();
+    let compressed = fs::read(&path).map_err(|err| match err.kind() {
+        std::io::ErrorKind::NotFound => UranusError::BackupMissing {
+            path: path_name.clone(),
+        },
+        _ => UranusError::io(&path_name, err),
+    })?;
+
+    let mut content = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut content)
+        .map_err(|err| UranusError::BackupCorrupt {
+            path: path_name.clone(),
+            reason: err.to_string(),
+        })?;
+    if hash_content(&content) != hash {
+        return Err(UranusError::BackupCorrupt {
+            path: path_name,
+            reason: "content does not match its hash".to_string(),
+        });
+    }
+    Ok(content)
+}
+
+fn load_index() -> Result<Index, UranusError> {
+    let cached = BATCH.with(|batch| {
+        batch
+            .borrow()
+            .as_ref()
+            .and_then(|batch| batch.index.clone())
+    });
+    if let Some(index) = cached {
+        return Ok(index);
+    }
+    let index = read_index()?;
+    BATCH.with(|batch| {
+        if let Some(batch) = batch.borrow_mut().as_mut() {
+            batch.index = Some(index.clone());
+        }
+    });
+    Ok(index)
+}
+
+fn read_index() -> Result<Index, UranusError> {
+    let path = index_path();
+    if !Path::new(&path).exists() {
+        return Ok(Index::default());
+    }
+    let content = read_from_file(&path)?;
+    toml::from_str(&content).map_err(|err| UranusError::BackupCorrupt {
+        path,
+        reason: err.to_string(),
+    })
+}
+
+fn save_index(index: &Index) -> Result<(), UranusError> {
+    let batched = BATCH.with(|batch| match batch.borrow_mut().as_mut() {
+        Some(batch) => {
+            batch.index = Some(index.clone());
+            batch.changed = true;
+            true
+        }
+        None => false,
+    });
+    if batched {
+        return Ok(());
+    }
+    write_index(index)
+}
+
+fn write_index(index: &Index) -> Result<(), UranusError> {
+    let path = index_path();
+    let content = toml::to_string(index).map_err(|err| UranusError::BackupCorrupt {
+        path: path.clone(),
+        reason: err.to_string(),
+    })?;
+    write_atomic(Path::new(&path), content.as_bytes())
+}
+
+/// Run the operation with the index kept in memory, so its backups and marks
+/// are written at once when it ends, even if it failed. An operation within
+/// another one is part of it.
+pub fn batch<R>(operation: impl FnOnce() -> R) -> Result<R, UranusError> {
+    let nested = BATCH.with(|batch| {
+        let mut batch = batch.borrow_mut();
+        let nested = batch.is_some();
+        batch.get_or_insert_with(Batch::default);
+        nested
+    });
+    if nested {
+        return Ok(operation());
+    }
+    let result = operation();
+    if let Some(Batch {
+        index: Some(index),
+        changed: true,
+    }) = BATCH.with(|batch| batch.borrow_mut().take())
+    {
+        write_index(&index)?;
+    }
+    Ok(result)
+}
+
+/// Hash of the content of the backup.
+pub fn backup_hash(key: &str) -> Result<Option<String>, UranusError> {
+    Ok(load_index()?.backups.get(key).cloned())
+}
+
+/// Keys of the backups under the backup, e.g. the files of a directory.
+pub fn backups_under(key: &str) -> Result<Vec<(String, String)>, UranusError> {
+    let prefix = Path::new(key);
+    Ok(load_index()?
+        .backups
+        .into_iter()
+        .filter(|(value, _)| Path::new(value).starts_with(prefix) && value != key)
+        .collect())
+}
+
+/// Whether the backups of the legacy home came from the Scripts folder: each
+/// backed up file is there, and one at least is the same as its backup, the
+/// others being patched by the mods. The legacy home was shared by all
+/// installations, so it tells no folder itself.
+fn is_legacy_folder(legacy: &[(PathBuf, String)], hades_path: &str) -> bool {
+    let mut same = false;
+    for (relative, hash) in legacy {
+        match fs::read(Path::new(hades_path).join(relative)) {
+            Ok(content) => same |= hash_content(&content) == *hash,
+            Err(_) => return false,
+        }
+    }
+    same
+}
+
+/// Record the Scripts folder of the backup home. The backups of the legacy
+/// home are moved to it if they came from that folder, otherwise they are
+/// left for the next folder registered.
+pub fn register_home(
+    backup_home: &str,
+    hades_path: &str,
+    legacy_home: &str,
+) -> Result<(), UranusError> {
+    let mut index = load_index()?;
+    let legacy_prefix = Path::new(legacy_home);
+    let legacy_keys: Vec<String> = index
+        .backups
+        .keys()
+        .filter(|key| Path::new(key).starts_with(legacy_prefix))
+        .cloned()
+        .collect();
+    let registered = index.homes.get(backup_home).map(String::as_str) == Some(hades_path);
+    if registered && legacy_keys.is_empty() {
+        return Ok(());
+    }
+    index
+        .homes
+        .insert(backup_home.to_string(), hades_path.to_string());
+
+    let legacy: Vec<(PathBuf, String)> = legacy_keys
+        .iter()
+        .filter_map(|key| {
+            let relative = Path::new(key).strip_prefix(legacy_prefix).ok()?;
+            Some((relative.to_path_buf(), index.backups.get(key)?.clone()))
+        })
+        .collect();
+    if !legacy.is_empty() && !is_legacy_folder(&legacy, hades_path) {
+        log::warn!(
+            "Store: {} backups of `{}` are not of `{}`, left unassigned.",
+            legacy.len(),
+            legacy_home,
+            hades_path
+        );
+        return save_index(&index);
+    }
+    for key in &legacy_keys {
+        if let Some(hash) = index.backups.remove(key) {
+            let relative = Path::new(key)
+                .strip_prefix(legacy_prefix)
+                .unwrap_or(Path::new(""));
+            let new_key = Path::new(backup_home).join(relative);
+            index
+                .backups
+                .entry(new_key.to_string_lossy().to_string())
+                .or_insert(hash);
+        }
+    }
+    if !legacy_keys.is_empty() {
+        log::info!(
+            "Store: {} backups of `{}` moved to `{}` of `{}`.",
+            legacy_keys.len(),
+            legacy_home,
+            backup_home,
+            hades_path
+        );
+    }
+    save_index(&index)
+}
+
+/// Scripts folders of the backup homes.
+pub fn homes() -> Result<BTreeMap<String, String>, UranusError> {
+    Ok(load_index()?.homes)
+}
+
+/// Keep the content as the backup of the key, an existing backup is kept.
+pub fn backup(key: &str, content: &[u8]) -> Result<(), UranusError> {
+    let mut index = load_index()?;
+    if index.backups.contains_key(key) {
+        return Ok(());
+    }
+    let hash = put(content)?;
+    index.backups.insert(key.to_string(), hash);
+    index.created.remove(key);
+    save_index(&index)
+}
+
+/// Record the file of the key as created by the mods, so it is removed
+/// rather than restored.
+pub fn mark_created(key: &str) -> Result<(), UranusError> {
+    let mut index = load_index()?;
+    if index.backups.contains_key(key) || !index.created.insert(key.to_string()) {
+        return Ok(());
+    }
+    save_index(&index)
+}
+
+pub fn unmark_created(key: &str) -> Result<(), UranusError> {
+    let mut index = load_index()?;
+    if index.created.remove(key) {
+        save_index(&index)?;
+    }
+    Ok(())
+}
+
+pub fn is_created(key: &str) -> Result<bool, UranusError> {
+    Ok(load_index()?.created.contains(key))
+}
+
+/// Keys of the files created by the mods under the key.
+pub fn created_under(key: &str) -> Result<Vec<String>, UranusError> {
+    let prefix = Path::new(key);
+    Ok(load_index()?
+        .created
+        .into_iter()
+        .filter(|value| Path::new(value).starts_with(prefix) && value != key)
+        .collect())
+}
+
+/// Content of the backup, none if it was not backed up.
+pub fn read_backup(key: &str) -> Result<Option<Vec<u8>>, UranusError> {
+    match backup_hash(key)? {
+        Some(hash) => get(&hash).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Objects of the store, as (hash, path).
+fn objects() -> Vec<(String, PathBuf)> {
+    let mut objects = Vec::new();
+    let prefixes = fs::read_dir(store_dir().join(OBJECTS_DIR))
+        .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+        .unwrap_or_else(|_| Vec::new());
+    for prefix in prefixes {
+        let entries = fs::read_dir(&prefix)
+            .map(|entries| entries.flatten().map(|entry| entry.path()).collect())
+            .unwrap_or_else(|_| Vec::new());
+        for path in entries {
+            let hash = path.file_name().unwrap_or_default().to_string_lossy();
+            objects.push((hash.to_string(), path));
+        }
+    }
+    objects.sort();
+    objects
+}
+
+/// Check every object against its hash, returns the broken ones and
+/// the backups which lost their object.
+pub fn verify() -> Result<Vec<UranusError>, UranusError> {
+    let mut errors = Vec::new();
+    for (hash, _) in objects() {
+        if let Err(err) = get(&hash) {
+            errors.push(err);
+        }
+    }
+    for (key, hash) in load_index()?.backups {
+        if !contains(&hash) {
+            errors.push(UranusError::BackupMissing { path: key });
+        }
+    }
+    Ok(errors)
+}
+
+/// Remove the objects which no backup or snapshot has, returns the count
+/// and the bytes removed.
+pub fn gc() -> Result<(usize, u64), UranusError> {
+    let mut used: HashSet<String> = load_index()?.backups.into_values().collect();
+    for snapshot in snapshot::list(None) {
+        used.extend(snapshot.files.into_iter().map(|file| file.hash));
+    }
+
+    let (mut count, mut bytes) = (0, 0);
+    for (hash, path) in objects() {
+        if used.contains(&hash) {
+            continue;
+        }
+        let size = fs::metadata(&path).map_or(0, |metadata| metadata.len());
+        fs::remove_file(&path).map_err(|err| UranusError::io(path.to_string_lossy(), err))?;
+        count += 1;
+        bytes += size;
+    }
+    log::info!("Store: removed {} objects, {} bytes.", count, bytes);
+    Ok((count, bytes))
+}
+
+/// Move the backups copied into the backup home by older versions into the
+/// store, under the same keys.
+pub fn import_dir(backup_home: &str) -> Result<usize, UranusError> {
+    let dir = Path::new(backup_home);
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+    let mut count = 0;
+    for entry in fs::read_dir(dir).map_err(|err| UranusError::io(backup_home, err))? {
+        let path = entry
+            .map_err(|err| UranusError::io(backup_home, err))?
+            .path();
+        let path_name = path.to_string_lossy().to_string();
+        if path.is_dir() {
+            count += import_dir(&path_name)?;
+        } else {
+            let content = fs::read(&path).map_err(|err| UranusError::io(&path_name, err))?;
+            backup(&origin_path(backup_home, &path_name), &content)?;
+            count += 1;
+        }
+    }
+    fs::remove_dir_all(dir).map_err(|err| UranusError::io(backup_home, err))?;
+    Ok(count)
+}
+
+/// Write the content to the file unless it is the same.
+pub fn restore_file(file_path: &str, content: &[u8]) -> Result<bool, UranusError> {
+    if fs::read(file_path).map_or(false, |current| current == content) {
+        return Ok(false);
+    }
+    fs::write(file_path, content).map_err(|err| UranusError::io(file_path, err))?;
+    Ok(true)
+}
@@ -0,0 +1,169 @@
+use std::fmt;
+
+use iced::theme::Palette;
+use iced::{Color, Theme};
+use serde::Deserialize;
+
+use crate::paths;
+use crate::utils::read_from_file;
+
+/// Colors of the panel, `palette` styles the widgets and the toasts,
+/// `accent` the titles and `highlight` the rollback text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UranusTheme {
+    pub name: String,
+    pub palette: Palette,
+    pub accent: Color,
+    pub highlight: Color,
+}
+
+impl UranusTheme {
+    /// Theme of iced built from the palette.
+    pub fn iced(&self) -> Theme {
+        Theme::custom(self.palette)
+    }
+
+    /// The default theme.
+    pub fn dark() -> Self {
+        UranusTheme {
+            name: "Dark".to_string(),
+            palette: Palette {
+                background: Color::from_rgb8(32, 34, 37),
+                text: Color::from_rgb(0.90, 0.90, 0.90),
+                primary: Color::from_rgb8(120, 5, 0),
+                success: Color::from_rgb8(18, 102, 79),
+                danger: Color::from_rgb8(195, 66, 63),
+            },
+            accent: Color::from_rgb8(255, 120, 5),
+            highlight: Color::from_rgb8(0, 180, 150),
+        }
+    }
+
+    pub fn light() -> Self {
+        UranusTheme {
+            name: "Light".to_string(),
+            palette: Palette {
+                background: Color::from_rgb8(245, 242, 238),
+                text: Color::from_rgb8(30, 30, 30),
+                primary: Color::from_rgb8(150, 20, 10),
+                success: Color::from_rgb8(18, 122, 89),
+                danger: Color::from_rgb8(195, 46, 43),
+            },
+            accent: Color::from_rgb8(190, 80, 0),
+            highlight: Color::from_rgb8(0, 120, 100),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        UranusTheme {
+            name: "High Contrast".to_string(),
+            palette: Palette {
+                background: Color::BLACK,
+                text: Color::WHITE,
+                primary: Color::from_rgb8(0, 80, 200),
+                success: Color::from_rgb8(0, 150, 0),
+                danger: Color::from_rgb8(220, 0, 0),
+            },
+            accent: Color::from_rgb8(255, 230, 0),
+            highlight: Color::from_rgb8(0, 255, 255),
+        }
+    }
+}
+
+impl Default for UranusTheme {
+    fn default() -> Self {
+        UranusTheme::dark()
+    }
+}
+
+impl fmt::Display for UranusTheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.name.fmt(f)
+    }
+}
+
+/// A theme of "themes.toml", colors are written as "#rrggbb".
+#[derive(Debug, Deserialize)]
+struct ThemeEntry {
+    name: String,
+    background: String,
+    text: String,
+    primary: String,
+    success: String,
+    danger: String,
+    accent: String,
+    highlight: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ThemeFile {
+    #[serde(default, rename = "theme")]
+    themes: Vec<ThemeEntry>,
+}
+
+impl ThemeEntry {
+    fn to_theme(&self) -> Result<UranusTheme, String> {
+        Ok(UranusTheme {
+            name: self.name.clone(),
+            palette: Palette {
+                background: parse_color(&self.background)?,
+                text: parse_color(&self.text)?,
+                primary: parse_color(&self.primary)?,
+                success: parse_color(&self.success)?,
+                danger: parse_color(&self.danger)?,
+            },
+            accent: parse_color(&self.accent)?,
+            highlight: parse_color(&self.highlight)?,
+        })
+    }
+}
+
+/// Parse "#rrggbb" or "rrggbb".
+fn parse_color(value: &str) -> Result<Color, String> {
+    let hex = value.trim().trim_start_matches('#');
+    let channel = |index: usize| {
+        hex.get(index..index + 2)
+            .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+    };
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(r), Some(g), Some(b)) => Ok(Color::from_rgb8(r, g, b)),
+        _ => Err(format!("color `{}` is not #rrggbb", value)),
+    }
+}
+
+/// The built-in themes followed by the themes of "themes.toml" in the
+/// config directory, a theme of the file replaces the built-in one of the same name.
+pub fn load() -> Vec<UranusTheme> {
+    let mut themes = vec![
+        UranusTheme::dark(),
+        UranusTheme::light(),
+        UranusTheme::high_contrast(),
+    ];
+
+    let themes_file = paths::config_dir().join("themes.toml");
+    if !themes_file.exists() {
+        return themes;
+    }
+    let themes_file = themes_file.to_string_lossy();
+    let theme_file: ThemeFile = match read_from_file(&themes_file)
+        .map_err(|err| err.to_string())
+        .and_then(|content| toml::from_str(&content).map_err(|err| err.to_string()))
+    {
+        Ok(theme_file) => theme_file,
+        Err(err) => {
+            log::warn!("load themes `{}` failed, {}", themes_file, err);
+            return themes;
+        }
+    };
+
+    for entry in theme_file.themes {
+        match entry.to_theme() {
+            Ok(theme) => match themes.iter_mut().find(|value| value.name == theme.name) {
+                Some(value) => *value = theme,
+                None => themes.push(theme),
+            },
+            Err(err) => log::warn!("theme `{}` is skipped, {}", entry.name, err),
+        }
+    }
+    themes
+}
@@ -14,9 +14,11 @@ use iced::widget::{button, column, container, horizontal_rule, horizontal_space,
 use iced::window;
 use iced::{Alignment, Element, Length, Point, Rectangle, Renderer, Size, Theme, Vector};
 
-use crate::panel::{YY_FONT, ICON_FONT};
+use crate::panel::{ICON_FONT, YY_FONT};
 
 pub const DEFAULT_TIMEOUT: u64 = 5;
+pub const DEFAULT_ACTION_TIMEOUT: u64 = 10;
+pub const DEFAULT_MAX_VISIBLE: usize = 5;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Status {
@@ -64,17 +66,65 @@ impl fmt::Display for Status {
     }
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct Toast {
+/// A button of the toast which emits the message when pressed.
+#[derive(Debug, Clone)]
+pub struct Action<Message> {
+    pub label: String,
+    pub message: Message,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast<Message> {
+    // unique id, toasts are closed by id
+    pub id: usize,
     pub title: String,
     pub body: String,
     pub status: Status,
+    // details which can be expanded, such as the error chain
+    pub detail: Option<String>,
+    pub expanded: bool,
+    pub actions: Vec<Action<Message>>,
+}
+
+impl<Message> Toast<Message> {
+    pub fn new(title: impl Into<String>, body: impl Into<String>, status: Status) -> Self {
+        Toast {
+            id: 0,
+            title: title.into(),
+            body: body.into(),
+            status,
+            detail: None,
+            expanded: false,
+            actions: Vec::new(),
+        }
+    }
+
+    pub fn detail(self, detail: impl Into<String>) -> Self {
+        Self {
+            detail: Some(detail.into()),
+            ..self
+        }
+    }
+
+    pub fn action(mut self, label: impl Into<String>, message: Message) -> Self {
+        self.actions.push(Action {
+            label: label.into(),
+            message,
+        });
+        self
+    }
 }
 
 pub struct Manager<'a, Message> {
     content: Element<'a, Message>,
     toasts: Vec<Element<'a, Message>>,
+    // id, status and whether it has actions of each toast element
+    toast_ids: Vec<(usize, Status, bool)>,
     timeout_secs: u64,
+    // timeouts for status, `None` stays until dismissed
+    status_timeouts: Vec<(Status, Option<u64>)>,
+    // least timeout of toasts with actions, `None` stays until dismissed
+    action_timeout: Option<u64>,
     on_close: Box<dyn Fn(usize) -> Message + 'a>,
 }
 
@@ -84,20 +134,52 @@ where
 {
     pub fn new(
         content: impl Into<Element<'a, Message>>,
-        toasts: &'a [Toast],
+        toasts: &'a [Toast<Message>],
         on_close: impl Fn(usize) -> Message + 'a,
+        on_toggle: impl Fn(usize) -> Message + 'a,
     ) -> Self {
+        let toast_ids = toasts
+            .iter()
+            .map(|toast| (toast.id, toast.status, !toast.actions.is_empty()))
+            .collect();
         let toasts = toasts
             .iter()
-            .enumerate()
-            .map(|(index, toast)| {
+            .map(|toast| {
+                // body with a button to expand the detail
+                let mut body = row![text(toast.body.as_str()).font(YY_FONT)]
+                    .spacing(5)
+                    .align_items(Alignment::Center);
+                if toast.detail.is_some() {
+                    body = body.push(horizontal_space(Length::Fill)).push(
+                        button(text(if toast.expanded { "-" } else { "+" }).font(YY_FONT))
+                            .on_press((on_toggle)(toast.id))
+                            .style(theme::Button::Text)
+                            .padding(3),
+                    );
+                }
+                let mut body = column![body].spacing(5);
+                if let (Some(detail), true) = (&toast.detail, toast.expanded) {
+                    body = body.push(text(detail.as_str()).font(YY_FONT).size(12));
+                }
+                if !toast.actions.is_empty() {
+                    let mut actions = row![].spacing(5);
+                    for action in &toast.actions {
+                        actions = actions.push(
+                            button(text(action.label.as_str()).font(YY_FONT).size(14))
+                                .on_press(action.message.clone())
+                                .padding(3),
+                        );
+                    }
+                    body = body.push(actions);
+                }
+
                 container(column![
                     container(
                         row![
                             text(toast.title.as_str()).font(YY_FONT),
                             horizontal_space(Length::Fill),
                             button(Text::new("\u{e901}").font(ICON_FONT))
-                                .on_press((on_close)(index))
+                                .on_press((on_close)(toast.id))
                                 .padding(3),
                         ]
                         .align_items(Alignment::Center)
@@ -106,12 +188,12 @@ where
                     .padding(5)
                     .style(theme::Container::Custom(Box::new(toast.status))),
                     horizontal_rule(1),
-                    container(text(toast.body.as_str()).font(YY_FONT))
+                    container(body)
                         .width(Length::Fill)
                         .padding(5)
                         .style(theme::Container::Box),
                 ])
-                .max_width(150)
+                .max_width(if toast.expanded { 300 } else { 150 })
                 .into()
             })
             .collect();
@@ -119,17 +201,58 @@ where
         Self {
             content: content.into(),
             toasts,
+            toast_ids,
             timeout_secs: DEFAULT_TIMEOUT,
+            status_timeouts: vec![(Status::Danger, None)],
+            action_timeout: Some(DEFAULT_ACTION_TIMEOUT),
             on_close: Box::new(on_close),
         }
+        .max_visible(DEFAULT_MAX_VISIBLE)
     }
 
+    /// Timeout of toasts whose status has no specific timeout.
     pub fn timeout(self, seconds: u64) -> Self {
         Self {
             timeout_secs: seconds,
             ..self
         }
     }
+
+    /// Timeout of toasts with the status, `None` stays until dismissed.
+    pub fn status_timeout(mut self, status: Status, seconds: Option<u64>) -> Self {
+        self.status_timeouts.retain(|(value, _)| *value != status);
+        self.status_timeouts.push((status, seconds));
+        self
+    }
+
+    /// Least timeout of toasts with actions, so that they can be clicked,
+    /// `None` stays until dismissed.
+    pub fn action_timeout(self, seconds: Option<u64>) -> Self {
+        Self {
+            action_timeout: seconds,
+            ..self
+        }
+    }
+
+    /// Only show the oldest toasts, the others wait in order for them to be
+    /// closed, their timeouts start when they are shown.
+    pub fn max_visible(mut self, count: usize) -> Self {
+        self.toasts.truncate(count);
+        self.toast_ids.truncate(count);
+        self
+    }
+
+    fn timeout_of(&self, status: Status, has_actions: bool) -> Option<u64> {
+        let timeout = self
+            .status_timeouts
+            .iter()
+            .find(|(value, _)| *value == status)
+            .map_or(Some(self.timeout_secs), |(_, seconds)| *seconds);
+        match timeout {
+            Some(seconds) if has_actions => self.action_timeout.map(|action| action.max(seconds)),
+            _ => timeout,
+        }
+    }
 }
 
 impl<'a, Message> Widget<Message, Renderer> for Manager<'a, Message> {
@@ -146,12 +269,12 @@ impl<'a, Message> Widget<Message, Renderer> for Manager<'a, Message> {
     }
 
     fn tag(&self) -> widget::tree::Tag {
-        struct Marker(Vec<Instant>);
+        struct Marker(Vec<(usize, Option<Instant>)>);
         widget::tree::Tag::of::<Marker>()
     }
 
     fn state(&self) -> widget::tree::State {
-        widget::tree::State::new(Vec::<Option<Instant>>::new())
+        widget::tree::State::new(Vec::<(usize, Option<Instant>)>::new())
     }
 
     fn children(&self) -> Vec<Tree> {
@@ -161,22 +284,22 @@ impl<'a, Message> Widget<Message, Renderer> for Manager<'a, Message> {
     }
 
     fn diff(&self, tree: &mut Tree) {
-        let instants = tree.state.downcast_mut::<Vec<Option<Instant>>>();
-
-        // Invalidating removed instants to None allows us to remove
-        // them here so that diffing for removed / new toast instants
-        // is accurate
-        instants.retain(Option::is_some);
+        let instants = tree.state.downcast_mut::<Vec<(usize, Option<Instant>)>>();
 
-        match (instants.len(), self.toasts.len()) {
-            (old, new) if old > new => {
-                instants.truncate(new);
-            }
-            (old, new) if old < new => {
-                instants.extend(std::iter::repeat(Some(Instant::now())).take(new - old));
-            }
-            _ => {}
-        }
+        // Instants are matched by toast id, so removing any toast or showing
+        // a waiting one keeps the others' timing, and new toasts start now
+        let matched = self
+            .toast_ids
+            .iter()
+            .map(|(id, _, _)| {
+                instants
+                    .iter()
+                    .find(|(value, _)| value == id)
+                    .copied()
+                    .unwrap_or((*id, Some(Instant::now())))
+            })
+            .collect();
+        *instants = matched;
 
         tree.diff_children(
             &std::iter::once(&self.content)
@@ -266,7 +389,12 @@ impl<'a, Message> Widget<Message, Renderer> for Manager<'a, Message> {
         layout: Layout<'_>,
         renderer: &Renderer,
     ) -> Option<overlay::Element<'b, Message, Renderer>> {
-        let instants = state.state.downcast_mut::<Vec<Option<Instant>>>();
+        let instants = state.state.downcast_mut::<Vec<(usize, Option<Instant>)>>();
+        let timeouts = self
+            .toast_ids
+            .iter()
+            .map(|(_, status, has_actions)| self.timeout_of(*status, *has_actions))
+            .collect();
 
         let (content_state, toasts_state) = state.children.split_at_mut(1);
 
@@ -282,8 +410,8 @@ impl<'a, Message> Widget<Message, Renderer> for Manager<'a, Message> {
                     toasts: &mut self.toasts,
                     state: toasts_state,
                     instants,
+                    timeouts,
                     on_close: &self.on_close,
-                    timeout_secs: self.timeout_secs,
                 }),
             )
         });
@@ -296,9 +424,9 @@ impl<'a, Message> Widget<Message, Renderer> for Manager<'a, Message> {
 struct Overlay<'a, 'b, Message> {
     toasts: &'b mut [Element<'a, Message>],
     state: &'b mut [Tree],
-    instants: &'b mut [Option<Instant>],
+    instants: &'b mut [(usize, Option<Instant>)],
+    timeouts: Vec<Option<u64>>,
     on_close: &'b dyn Fn(usize) -> Message,
-    timeout_secs: u64,
 }
 
 impl<'a, 'b, Message> overlay::Overlay<Message, Renderer> for Overlay<'a, 'b, Message> {
@@ -331,17 +459,19 @@ impl<'a, 'b, Message> overlay::Overlay<Message, Renderer> for Overlay<'a, 'b, Me
         if let Event::Window(window::Event::RedrawRequested(now)) = &event {
             let mut next_redraw: Option<window::RedrawRequest> = None;
 
-            self.instants
-                .iter_mut()
-                .enumerate()
-                .for_each(|(index, maybe_instant)| {
+            self.instants.iter_mut().zip(self.timeouts.iter()).for_each(
+                |((id, maybe_instant), timeout_secs)| {
+                    // stays until dismissed
+                    let Some(timeout_secs) = timeout_secs else {
+                        return;
+                    };
                     if let Some(instant) = maybe_instant.as_mut() {
-                        let remaining = Duration::from_secs(self.timeout_secs)
-                            .saturating_sub(instant.elapsed());
+                        let remaining =
+                            Duration::from_secs(*timeout_secs).saturating_sub(instant.elapsed());
 
                         if remaining == Duration::ZERO {
                             maybe_instant.take();
-                            shell.publish((self.on_close)(index));
+                            shell.publish((self.on_close)(*id));
                             next_redraw = Some(window::RedrawRequest::NextFrame);
                         } else {
                             let redraw_at = window::RedrawRequest::At(*now + remaining);
@@ -350,7 +480,8 @@ impl<'a, 'b, Message> overlay::Overlay<Message, Renderer> for Overlay<'a, 'b, Me
                                 .or(Some(redraw_at));
                         }
                     }
-                });
+                },
+            );
 
             if let Some(redraw) = next_redraw {
                 shell.request_redraw(redraw);
@@ -364,7 +495,7 @@ impl<'a, 'b, Message> overlay::Overlay<Message, Renderer> for Overlay<'a, 'b, Me
             .zip(self.state.iter_mut())
             .zip(layout.children())
             .zip(self.instants.iter_mut())
-            .map(|(((child, state), layout), instant)| {
+            .map(|(((child, state), layout), (_, instant))| {
                 let mut local_messages = vec![];
                 let mut local_shell = Shell::new(&mut local_messages);
 
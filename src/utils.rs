@@ -1,4 +1,4 @@
-use regex::Regex;
+use regex::{Captures, Regex};
 use same_file::Handle;
 use std::error::Error;
 use std::ffi::OsString;
@@ -6,65 +6,77 @@ use std::fs::{self, File};
 use std::io::{BufReader, Read, Write};
 use std::path::Path;
 
-use crate::components::CustomError;
+use crate::error::UranusError;
+use crate::store;
 
 /// Check if file is being read and written at the same time.
-fn check_same_file(file_path: &str) -> Result<(), Box<dyn Error>> {
+fn check_same_file(file_path: &str) -> Result<(), UranusError> {
     let path_to_read = Path::new(file_path);
-    let handle = Handle::from_path(path_to_read)?;
+    // a file not created yet can't be the same file
+    if !path_to_read.exists() {
+        return Ok(());
+    }
+    let handle = Handle::from_path(path_to_read).map_err(|err| UranusError::io(file_path, err))?;
 
-    let stdout_handle = Handle::stdout()?;
+    let stdout_handle = Handle::stdout().map_err(|err| UranusError::io("stdout", err))?;
     if stdout_handle == handle {
-        //     return Box::new(Err(io::Error::new(
-        //         ErrorKind::Other,
-        //         "reading and writing to the same file",
-        //     )));
-        // }
-        return Err(CustomError::new("reading and writing to the same file"));
+        return Err(UranusError::SameFile {
+            path: file_path.to_string(),
+        });
     }
     Ok(())
 }
 
 /// Read content from file.
-pub fn read_from_file(file_path: &str) -> Result<String, Box<dyn Error>> {
+pub fn read_from_file(file_path: &str) -> Result<String, UranusError> {
     check_same_file(file_path)?;
-    let file = File::open(Path::new(file_path))?;
+    let file = File::open(Path::new(file_path)).map_err(|err| UranusError::io(file_path, err))?;
 
     let mut file = BufReader::new(file);
     let mut content = String::new();
-    file.read_to_string(&mut content)?;
+    file.read_to_string(&mut content)
+        .map_err(|err| UranusError::io(file_path, err))?;
     Ok(content)
 }
 
 /// Write content to file.
-pub fn write_to_file(file_path: &str, content: &str) -> Result<(), Box<dyn Error>> {
+pub fn write_to_file(file_path: &str, content: &str) -> Result<(), UranusError> {
     check_same_file(file_path)?;
 
-    let mut file = File::create(Path::new(file_path))?;
-    file.write_all(content.as_bytes())?;
-    file.flush()?;
+    let mut file =
+        File::create(Path::new(file_path)).map_err(|err| UranusError::io(file_path, err))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.flush())
+        .map_err(|err| UranusError::io(file_path, err))?;
     Ok(())
 }
 
 /// Copy a directory from src to dest.
-fn copy_directory(src: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+fn copy_directory(src: &Path, dest: &Path) -> Result<(), UranusError> {
+    let io_error = |path: &Path| {
+        let path = path.to_string_lossy().to_string();
+        move |err| UranusError::io(path, err)
+    };
+
     if !src.is_dir() {
-        return Err(CustomError::new("source is not a directory"));
+        return Err(UranusError::PathNotFound {
+            path: src.to_string_lossy().to_string(),
+        });
     }
 
     if !dest.exists() {
-        fs::create_dir_all(dest)?;
+        fs::create_dir_all(dest).map_err(io_error(dest))?;
     }
 
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
+    for entry in fs::read_dir(src).map_err(io_error(src))? {
+        let entry = entry.map_err(io_error(src))?;
         let entry_path = entry.path();
         let dest_path = dest.join(entry_path.file_name().unwrap());
 
         if entry_path.is_dir() {
             copy_directory(&entry_path, &dest_path)?;
         } else {
-            fs::copy(&entry_path, &dest_path)?;
+            fs::copy(&entry_path, &dest_path).map_err(io_error(&dest_path))?;
         }
     }
 
@@ -72,108 +84,253 @@ fn copy_directory(src: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
 }
 
 /// Copy a file from src to dest.
-fn copy_file(src: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+fn copy_file(src: &Path, dest: &Path) -> Result<(), UranusError> {
     if !src.is_file() {
-        return Err(CustomError::new("source is not a directory"));
+        return Err(UranusError::PathNotFound {
+            path: src.to_string_lossy().to_string(),
+        });
     }
 
+    let dest_name = dest.to_string_lossy().to_string();
     if !dest.parent().unwrap().exists() {
-        fs::create_dir_all(dest.parent().unwrap())?;
+        fs::create_dir_all(dest.parent().unwrap())
+            .map_err(|err| UranusError::io(&dest_name, err))?;
     }
 
-    fs::copy(&src, &dest)?;
+    fs::copy(&src, &dest).map_err(|err| UranusError::io(&dest_name, err))?;
     Ok(())
 }
 
-/// Backup from origin(src) to dest.
-pub fn backup_from_origin(src: &str, dest: &str) -> Result<(), Box<dyn Error>> {
-    let src_path = Path::new(src);
-    let dest_path = Path::new(dest);
-
-    let file_name = match src_path.file_name() {
-        Some(value) => value.to_os_string(),
-        None => OsString::from(""),
-    };
-
-    let binding = dest_path.join(file_name);
-    let dest_path = binding.as_path();
-
-    // Avoid backing up modified files
-    if dest_path.exists() {
+/// Move a directory or file from src to dest, copying if renaming failed,
+/// e.g. across drives.
+pub fn move_path(src: &Path, dest: &Path) -> Result<(), UranusError> {
+    let dest_name = dest.to_string_lossy().to_string();
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|err| UranusError::io(&dest_name, err))?;
+    }
+    if fs::rename(src, dest).is_ok() {
         return Ok(());
     }
 
+    let src_name = src.to_string_lossy().to_string();
+    if src.is_dir() {
+        copy_directory(src, dest)?;
+        fs::remove_dir_all(src).map_err(|err| UranusError::io(&src_name, err))
+    } else {
+        copy_file(src, dest)?;
+        fs::remove_file(src).map_err(|err| UranusError::io(&src_name, err))
+    }
+}
+
+/// Backup from origin(src) into the store under the backup home(dest),
+/// the files of a directory are backed up one by one.
+pub fn backup_from_origin(src: &str, dest: &str) -> Result<(), UranusError> {
+    let src_path = Path::new(src);
+    let key = origin_path(dest, src);
+
     if src_path.is_dir() {
-        copy_directory(src_path, dest_path)?;
+        for entry in fs::read_dir(src_path).map_err(|err| UranusError::io(src, err))? {
+            let entry = entry.map_err(|err| UranusError::io(src, err))?;
+            backup_from_origin(&entry.path().to_string_lossy(), &key)?;
+        }
     } else if src_path.is_file() {
-        copy_file(src_path, dest_path)?;
+        // Avoid backing up modified files
+        if store::backup_hash(&key)?.is_some() {
+            return Ok(());
+        }
+        let content = fs::read(src_path).map_err(|err| UranusError::io(src, err))?;
+        store::backup(&key, &content)?;
     } else {
-        let message = format!("source `{:?}`is not a directory or file", src_path);
-        return Err(CustomError::new(&message));
+        // nothing to back up, the file will be created by the mod
+        store::mark_created(&key)?;
     }
     Ok(())
 }
 
-/// Rollback src to origin(dest).
-pub fn rollback_to_origin(src: &str, dest: &str) -> Result<(), Box<dyn Error>> {
-    let src_path = Path::new(src);
+/// Rollback origin(dest) from its backup under the backup home(src).
+pub fn rollback_to_origin(src: &str, dest: &str) -> Result<(), UranusError> {
     let dest_path = Path::new(dest);
+    let key = origin_path(src, dest);
 
-    let file_name = match dest_path.file_name() {
-        Some(value) => value.to_os_string(),
-        None => OsString::from(""),
-    };
+    if store::is_created(&key)? {
+        // the file did not exist before the mod
+        if dest_path.is_file() {
+            fs::remove_file(dest_path).map_err(|err| UranusError::io(dest, err))?;
+        }
+        store::unmark_created(&key)?;
+    } else if dest_path.is_dir() {
+        let backups = store::backups_under(&key)?;
+        if backups.is_empty() {
+            return Err(UranusError::BackupMissing { path: key });
+        }
+        for (file_key, hash) in backups {
+            let relative = Path::new(&file_key)
+                .strip_prefix(&key)
+                .unwrap_or(Path::new(""));
+            let file_path = dest_path.join(relative);
+            let file_name = file_path.to_string_lossy().to_string();
+            if let Some(parent) = file_path.parent() {
+                fs::create_dir_all(parent).map_err(|err| UranusError::io(&file_name, err))?;
+            }
+            store::restore_file(&file_name, &store::get(&hash)?)?;
+        }
+    } else if dest_path.is_file() {
+        let content = store::read_backup(&key)?.ok_or(UranusError::BackupMissing { path: key })?;
+        store::restore_file(dest, &content)?;
+    } else {
+        return Err(UranusError::PathNotFound {
+            path: dest.to_string(),
+        });
+    }
+    Ok(())
+}
 
-    let binding = src_path.join(file_name);
-    let src_path = binding.as_path();
+/// Content of the origin backup of the file, none if not backed up.
+pub fn read_origin(backup_home: &str, file_path: &str) -> Result<Option<String>, UranusError> {
+    let content = store::read_backup(&origin_path(backup_home, file_path))?;
+    Ok(content.map(|content| String::from_utf8_lossy(&content).to_string()))
+}
 
-    if !src_path.exists() {
-        return Ok(());
+/// The `from`(regex pattern) which matches the content, `expected` times if given.
+fn matched_pattern(
+    file_path: &str,
+    content: &str,
+    from: &str,
+    expected: Option<usize>,
+) -> Result<Regex, UranusError> {
+    let pattern = Regex::new(from).map_err(|err| UranusError::InvalidPattern {
+        pattern: from.to_string(),
+        source: err,
+    })?;
+    let found = pattern.find_iter(content).count();
+    if found == 0 {
+        return Err(UranusError::PatternNotFound {
+            file: file_path.to_string(),
+            pattern: from.to_string(),
+        });
+    }
+    if let Some(expected) = expected {
+        if found != expected {
+            return Err(UranusError::UnexpectedMatchCount {
+                file: file_path.to_string(),
+                pattern: from.to_string(),
+                expected,
+                found,
+            });
+        }
     }
+    Ok(pattern)
+}
 
-    if dest_path.is_dir() {
-        copy_directory(src_path, dest_path)?;
-    } else if dest_path.is_file() {
-        copy_file(src_path, dest_path)?;
-    } else {
-        let message = format!("destination `{:?}`is not a directory or file", dest_path);
-        return Err(CustomError::new(&message));
+/// Replace the content to `to` according to the `from`(regex pattern) in memory,
+/// the pattern should match `expected` times if given.
+pub fn replace_content(
+    file_path: &str,
+    content: &str,
+    from: &str,
+    to: &str,
+    expected: Option<usize>,
+) -> Result<String, UranusError> {
+    let pattern = matched_pattern(file_path, content, from, expected)?;
+    Ok(pattern.replace_all(content, to).to_string())
+}
+
+/// The number multiplied by the factor, in the same precision, e.g. "10" by
+/// 1.5 is "15" and "0.05" by 3 is "0.15".
+fn scale_number(number: &str, factor: f64) -> String {
+    let value = number.parse::<f64>().unwrap_or_default() * factor;
+    match number.split_once('.') {
+        Some((_, decimals)) => format!("{:.*}", decimals.len(), value),
+        None => format!("{}", value.round() as i64),
     }
-    Ok(())
+}
+
+/// Multiply the numbers captured as `value` by the `from`(regex pattern) in
+/// memory, e.g. `Gems = (?P<value>\d+)`, the pattern should match `expected`
+/// times if given.
+pub fn scale_content(
+    file_path: &str,
+    content: &str,
+    from: &str,
+    factor: f64,
+    expected: Option<usize>,
+) -> Result<String, UranusError> {
+    let pattern = matched_pattern(file_path, content, from, expected)?;
+    let scaled = pattern.replace_all(content, |captures: &Captures| {
+        match (captures.get(0), captures.name("value")) {
+            (Some(matched), Some(value)) => format!(
+                "{}{}{}",
+                &content[matched.start()..value.start()],
+                scale_number(value.as_str(), factor),
+                &content[value.end()..matched.end()]
+            ),
+            // a pattern without `value` is left as it is
+            _ => captures[0].to_string(),
+        }
+    });
+    Ok(scaled.to_string())
+}
+
+/// Multiply the numbers of the file captured as `value` by the `from`(regex pattern).
+pub fn scale_regex(
+    file_path: &str,
+    from: &str,
+    factor: f64,
+    expected: Option<usize>,
+) -> Result<(), UranusError> {
+    let file_content = read_from_file(file_path)?;
+    let modify_content = scale_content(file_path, &file_content, from, factor, expected)?;
+    write_to_file(file_path, &modify_content)
 }
 
 /// Replace the content of file to `to` according to the `from`(regex pattern).
-pub fn replace_regex(file_path: &str, from: &str, to: &str) -> Result<(), Box<dyn Error>> {
+pub fn replace_regex(
+    file_path: &str,
+    from: &str,
+    to: &str,
+    expected: Option<usize>,
+) -> Result<(), UranusError> {
     let file_content = read_from_file(file_path)?;
 
-    let pattern = Regex::new(from).unwrap();
-    if !pattern.is_match(file_content.as_str()) {
-        return Err(CustomError::new("regex not match!"));
-    }
-    let modify_contnet = pattern.replace_all(file_content.as_str(), to).to_string();
+    let modify_contnet = replace_content(file_path, &file_content, from, to, expected)?;
 
-    match write_to_file(file_path, &modify_contnet) {
-        Ok(()) => Ok(()),
-        Err(err) => {
-            return Err(CustomError::new(
-                format!("write content failed, {}", err).as_str(),
-            ));
-        }
-    }
+    write_to_file(file_path, &modify_contnet)
 }
 
 /// Delete the content of file according to the `from`(regex pattern).
-pub fn delete_regex(file_path: &str, from: &str) -> Result<(), Box<dyn Error>> {
-    match replace_regex(file_path, from, "") {
-        Ok(()) => Ok(()),
-        Err(err) => {
-            return Err(CustomError::new(format!("{}", err).as_str()));
-        }
+pub fn delete_regex(
+    file_path: &str,
+    from: &str,
+    expected: Option<usize>,
+) -> Result<(), UranusError> {
+    replace_regex(file_path, from, "", expected)
+}
+
+/// Path of the origin backup of the file in backup home.
+pub fn origin_path(backup_home: &str, file_path: &str) -> String {
+    let file_name = match Path::new(file_path).file_name() {
+        Some(value) => value.to_os_string(),
+        None => OsString::from(""),
+    };
+    Path::new(backup_home)
+        .join(file_name)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Message of the error followed by the messages of its sources.
+pub fn error_chain(err: &dyn Error) -> String {
+    let mut chain = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        chain.push_str(&format!("\ncaused by: {}", err));
+        source = err.source();
     }
+    chain
 }
 
 /// Join a file name to path.
-pub fn join_path(hades_path: &str, file_name: &'static str) -> String {
+pub fn join_path(hades_path: &str, file_name: &str) -> String {
     let path = Path::new(&hades_path);
     path.join(file_name).to_string_lossy().to_string()
 }
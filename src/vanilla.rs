@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::Path;
+
+use crate::error::UranusError;
+use crate::paths;
+use crate::snapshot;
+use crate::store;
+
+/// Files of the Scripts folder touched by restoring the vanilla game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VanillaReport {
+    pub hades_path: String,
+    // written back from their backups
+    pub restored: Vec<String>,
+    // written back from the legacy backups of no installation, by their name
+    pub legacy: Vec<String>,
+    // already the same as their backups
+    pub unchanged: Vec<String>,
+    // created by the mods
+    pub removed: Vec<String>,
+}
+
+impl VanillaReport {
+    pub fn is_empty(&self) -> bool {
+        self.restored.is_empty()
+            && self.legacy.is_empty()
+            && self.unchanged.is_empty()
+            && self.removed.is_empty()
+    }
+
+    /// One line for each file touched.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        lines.extend(
+            self.restored
+                .iter()
+                .map(|name| format!("restored  {}", name)),
+        );
+        lines.extend(self.legacy.iter().map(|name| format!("legacy    {}", name)));
+        lines.extend(
+            self.removed
+                .iter()
+                .map(|name| format!("removed   {}", name)),
+        );
+        lines.extend(
+            self.unchanged
+                .iter()
+                .map(|name| format!("unchanged {}", name)),
+        );
+        lines
+    }
+}
+
+/// Path of the backed up file relative to the backup home, e.g. "TraitData.lua".
+fn relative_name(backup_home: &str, key: &str) -> String {
+    Path::new(key)
+        .strip_prefix(backup_home)
+        .unwrap_or(Path::new(key))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Restore every file of the Scripts folder which has a backup and remove the
+/// files created by the mods, whatever the panel believes is applied. All
+/// backups are checked before any file is touched, and the folder is
+/// snapshotted before, so the restore can be undone. The legacy backups left
+/// to no installation are used for the files of the folder without a backup.
+pub fn restore(hades_path: &str) -> Result<VanillaReport, UranusError> {
+    store::batch(|| restore_files(hades_path))?
+}
+
+fn restore_files(hades_path: &str) -> Result<VanillaReport, UranusError> {
+    let backup_home = &paths::backup_home(hades_path);
+    let mut contents = Vec::new();
+    for (key, hash) in store::backups_under(backup_home)? {
+        contents.push((relative_name(backup_home, &key), store::get(&hash)?, false));
+    }
+    let legacy_home = paths::legacy_backup_home();
+    for (key, hash) in store::backups_under(&legacy_home)? {
+        let name = relative_name(&legacy_home, &key);
+        if Path::new(hades_path).join(&name).is_file()
+            && !contents.iter().any(|(value, _, _)| *value == name)
+        {
+            contents.push((name, store::get(&hash)?, true));
+        }
+    }
+    let created = store::created_under(backup_home)?;
+
+    let mut report = VanillaReport {
+        hades_path: hades_path.to_string(),
+        ..VanillaReport::default()
+    };
+    if contents.is_empty() && created.is_empty() {
+        log::info!("Vanilla: no backups of `{}`.", hades_path);
+        return Ok(report);
+    }
+    snapshot::create_if_changed(hades_path, "before vanilla")?;
+
+    for (name, content, legacy) in contents {
+        let dest = Path::new(hades_path).join(&name);
+        let dest_name = dest.to_string_lossy().to_string();
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|err| UranusError::io(&dest_name, err))?;
+        }
+        if !store::restore_file(&dest_name, &content)? {
+            report.unchanged.push(name);
+        } else if legacy {
+            report.legacy.push(name);
+        } else {
+            report.restored.push(name);
+        }
+    }
+    for key in created {
+        let name = relative_name(backup_home, &key);
+        let dest = Path::new(hades_path).join(&name);
+        if dest.is_file() {
+            fs::remove_file(&dest).map_err(|err| UranusError::io(dest.to_string_lossy(), err))?;
+            report.removed.push(name);
+        }
+        store::unmark_created(&key)?;
+    }
+    log::info!(
+        "Vanilla: `{}` restored {:?}, legacy {:?}, removed {:?}.",
+        hades_path,
+        report.restored,
+        report.legacy,
+        report.removed
+    );
+    Ok(report)
+}
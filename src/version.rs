@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::UranusError;
+use crate::store;
+use crate::utils::origin_path;
+
+/// Scripts patched by the mods, the build of the game is told by them.
+pub const KEY_FILES: [&str; 9] = [
+    "ConsumableData.lua",
+    "FishingData.lua",
+    "HeroData.lua",
+    "LootData.lua",
+    "RoomManager.lua",
+    "RunManager.lua",
+    "StoreData.lua",
+    "TraitData.lua",
+    "TraitScripts.lua",
+];
+
+/// Whether a mod is known to work on the build of the installation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    // the fingerprint is one of those the mod was verified against
+    Verified,
+    // the mod was verified against other builds only
+    Untested,
+    // the mod tells no build
+    Undeclared,
+}
+
+impl Compatibility {
+    /// Compatibility of a mod verified against the fingerprints on the fingerprint.
+    pub fn of<'a>(verified: impl IntoIterator<Item = &'a str>, fingerprint: &str) -> Self {
+        let mut declared = false;
+        for value in verified {
+            if value == fingerprint {
+                return Compatibility::Verified;
+            }
+            declared = true;
+        }
+        if declared && !fingerprint.is_empty() {
+            Compatibility::Untested
+        } else {
+            Compatibility::Undeclared
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Compatibility::Verified => "verified",
+            Compatibility::Untested => "untested on this version",
+            Compatibility::Undeclared => "undeclared",
+        }
+    }
+}
+
+/// Hashes of the vanilla key files as (file name, sha256), the backups are
+/// read for the files changed by the mods, missing files are skipped.
+pub fn key_hashes(
+    hades_path: &str,
+    backup_home: &str,
+) -> Result<Vec<(String, String)>, UranusError> {
+    let mut hashes = Vec::new();
+    for name in KEY_FILES {
+        let path = Path::new(hades_path).join(name);
+        let path_name = path.to_string_lossy().to_string();
+        let content = match store::read_backup(&origin_path(backup_home, &path_name))? {
+            Some(content) => content,
+            None if path.is_file() => {
+                fs::read(&path).map_err(|err| UranusError::io(&path_name, err))?
+            }
+            None => continue,
+        };
+        hashes.push((name.to_string(), store::hash_content(&content)));
+    }
+    Ok(hashes)
+}
+
+/// Fingerprint of the build of the installation, the same vanilla key files
+/// have the same fingerprint whichever mods are applied.
+pub fn fingerprint(hades_path: &str, backup_home: &str) -> Result<String, UranusError> {
+    let mut hasher = Sha256::new();
+    for (name, hash) in key_hashes(hades_path, backup_home)? {
+        hasher.update(format!("{}:{}\n", name, hash));
+    }
+    Ok(format!("{:x}", hasher.finalize())[..16].to_string())
+}
@@ -0,0 +1,58 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, StreamExt};
+use iced::{subscription, Subscription};
+use notify::{RecursiveMode, Watcher};
+
+/// Paths of the directory which were created, modified or removed by any program.
+pub fn watch(dir: String) -> Subscription<PathBuf> {
+    subscription::channel(dir.clone(), 100, move |mut output| async move {
+        let (sender, mut receiver) = mpsc::unbounded();
+        let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            let _ = sender.unbounded_send(result);
+        })
+        .and_then(|mut watcher| {
+            watcher.watch(Path::new(&dir), RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        });
+
+        match watcher {
+            // the watcher stops when dropped
+            Ok(_watcher) => {
+                log::info!("Watcher: watching `{}`.", dir);
+                while let Some(result) = receiver.next().await {
+                    match result {
+                        Ok(event) => {
+                            if event.kind.is_create()
+                                || event.kind.is_modify()
+                                || event.kind.is_remove()
+                            {
+                                for path in event.paths {
+                                    let _ = output.send(path).await;
+                                }
+                            }
+                        }
+                        Err(err) => log::warn!("Watcher: `{}` failed, {}", dir, err),
+                    }
+                }
+            }
+            Err(err) => log::error!("Watcher: watch `{}` failed, {}", dir, err),
+        }
+
+        loop {
+            iced::futures::future::pending::<()>().await;
+        }
+    })
+}
+
+/// Hash of the file content, none if it can't be read.
+pub fn hash_file(path: &Path) -> Option<u64> {
+    let content = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    Some(hasher.finish())
+}
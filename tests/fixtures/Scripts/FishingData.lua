@@ -0,0 +1,24 @@
+-- excerpt of FishingData.lua
+FishingData =
+{
+	NumFakeDunks = { Min = 1, Max = 3 },
+	FakeDunkInterval = { Min = 0.8, Max = 3.0 },
+	WayLateInterval = 1.0,
+	GoodInterval = 0.6,
+	PerfectInterval = 0.34,
+	WrongInterval = 0.25,
+
+	BiomeFish =
+	{
+		Tartarus =
+		{
+			Good = { { Name = "Fish_Tartarus_Common_01", Weight = 0.95 }, { Name = "Fish_Tartarus_Rare_01", Weight = 0.05 } },
+			Perfect = { { Name = "Fish_Tartarus_Rare_01", Weight = 0.95 }, { Name = "Fish_Tartarus_Legendary_01", Weight = 0.05 } },
+		},
+		Asphodel =
+		{
+			Good = { { Name = "Fish_Asphodel_Common_01", Weight = 0.95 }, { Name = "Fish_Asphodel_Rare_01", Weight = 0.05 } },
+			Perfect = { { Name = "Fish_Asphodel_Rare_01", Weight = 0.95 }, { Name = "Fish_Asphodel_Legendary_01", Weight = 0.05 } },
+		},
+	},
+}
@@ -0,0 +1,38 @@
+-- excerpt of HeroData.lua
+HeroData =
+{
+	DefaultHero =
+	{
+		MaxHealth = 50,
+		MaxMana = 0,
+		StartingMoney = 0,
+		BoonData =
+		{
+			RareChance = 0.10,
+			EpicChance = 0.03,
+			HeroicChance = 0.0,
+			LegendaryChance = 0.01,
+			DuoChance = 0.12,
+			ReplaceChance = 0.10,
+		},
+		HermesData =
+		{
+			RareChance = 0.10,
+			EpicChance = 0.03,
+			HeroicChance = 0.0,
+			LegendaryChance = 0.01,
+		},
+		WeaponData =
+		{
+			RareChance = 0.0,
+			EpicChance = 0.0,
+			LegendaryChance = 0.0,
+		},
+		StackData =
+		{
+			RareChance = 0.0,
+			EpicChance = 0.0,
+			LegendaryChance = 0.0,
+		},
+	},
+}
@@ -0,0 +1,40 @@
+-- excerpt of LootData.lua
+LootData =
+{
+	BaseLoot =
+	{
+		DebugOnly = true,
+		GodLoot = false,
+		UseText = "UseLoot",
+		ResourceCosts = {},
+	},
+
+	StackUpgrade =
+	{
+		InheritFrom = { "BaseLoot" },
+		Name = "StackUpgrade",
+		Icon = "BoonSymbolPom",
+		UseText = "UseStackUpgrade",
+		StackOnly = true,
+		StackNum = 1,
+		RequiredMinAnyTraitStacks = 1,
+		MenuTitle = "StackUpgradeChoiceMenu_Title",
+	},
+
+	WeaponUpgrade =
+	{
+		InheritFrom = { "BaseLoot" },
+		Name = "WeaponUpgrade",
+		Icon = "WeaponUpgradeSymbol",
+		MaxChoices = 3,
+	},
+
+	ZeusUpgrade =
+	{
+		InheritFrom = { "BaseLoot" },
+		GodLoot = true,
+		Icon = "BoonSymbolZeus",
+		SpawnSound = "/SFX/ZeusBoonThunder",
+		Color = { 255, 255, 80, 255 },
+	},
+}
@@ -0,0 +1,25 @@
+-- excerpt of TraitData.lua
+TraitData =
+{
+	MaxHealthKeepsakeTrait =
+	{
+		Icon = "Keepsake_Collar",
+		InheritFrom = { "GiftTrait" },
+		InRackTitle = "MaxHealthKeepsakeTrait_Rack",
+		ChamberThresholds =  { 25, 50 },
+		PropertyChanges =
+		{
+			{
+				LuaProperty = "MaxHealth",
+				BaseValue = 25,
+				ChangeType = "Add",
+			},
+		},
+	},
+	DirectionalArmorTrait =
+	{
+		Icon = "Keepsake_Shield",
+		InheritFrom = { "GiftTrait" },
+		ChamberThresholds =  { 25, 50 },
+	},
+}
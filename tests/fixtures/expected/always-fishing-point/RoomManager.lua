@@ -0,0 +1,20 @@
+-- excerpt of RoomManager.lua
+function IsFishingEligible( currentRun, currentRoom )
+	if currentRoom.ForceFishing then
+		return true
+	end
+	if currentRun.CurrentRoom.FishingPointsSpawned ~= nil and currentRun.CurrentRoom.FishingPointsSpawned > 0 then
+		return false
+	end
+	return GetNumMetaUpgrades( "FishingUnlockItem" ) > 0
+end
+
+function ChooseFishingPoint( currentRun, currentRoom )
+	if currentRoom.FishingPointChance == nil then
+		return nil
+	end
+	if RandomChance( currentRoom.FishingPointChance )  then
+		return GetRandomValue( currentRoom.FishingPointIds )
+	end
+	return nil
+end
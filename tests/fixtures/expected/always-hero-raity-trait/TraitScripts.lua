@@ -0,0 +1,39 @@
+-- excerpt of TraitScripts.lua
+function SetTraitsOnLoot( lootData, args )
+	local rarityTable = GetRarityTable( lootData )
+	for i, upgradeData in ipairs( lootData.UpgradeOptions ) do
+		if rarityTable.Epic[upgradeData.ItemName] and lootData.RarityChances.Epic and RandomChance( lootData.RarityChances.Epic ) then
+			upgradeData.Rarity = "Epic"
+		elseif rarityTable.Heroic[upgradeData.ItemName] then
+			upgradeData.Rarity = "Legendary"
+		end
+	end
+end
+
+function GetUpgradedRarity( lootData, validRarities, rarityTable )
+	local chosenRarity = "Common"
+	local chosenUpgrade = nil
+	if validRarities.Legendary then
+		chosenRarity = "Legendary"
+		chosenUpgrade = GetRandomValue( rarityTable.Legendary )
+	elseif validRarities.Rare and lootData.RarityChances.Rare then
+            chosenRarity = "Rare"
+            chosenUpgrade = GetRandomValue( rarityTable.Rare )
+	end
+	if chosenUpgrade == nil then
+		return GetUpgradedRarityFallback( lootData, validRarities, rarityTable )
+	end
+	return chosenRarity, chosenUpgrade
+end
+
+function GetUpgradedRarityFallback( lootData, validRarities, rarityTable )
+        if validRarities.Heroic  then
+            chosenRarity = "Heroic"
+            chosenUpgrade = GetRandomValue( rarityTable.Heroic )
+	end
+	return chosenRarity, chosenUpgrade
+end
+
+function GetTotalLootChoices()
+	return 3 - GetNumMetaUpgrades( "ReducedLootChoicesShrineUpgrade" )
+end
@@ -0,0 +1,117 @@
+-- excerpt of ConsumableData.lua
+ConsumableData =
+{
+	BaseConsumable =
+	{
+		DebugOnly = true,
+		Cost = 0,
+		UseText = "UseLoot",
+		ConsumeSound = "/SFX/Player Sounds/IntroLootGrab",
+		PlayInteract = true,
+	},
+
+	RoomRewardMetaPointDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseMetaPointDrop",
+		RequiredTextLines = { "Ending01" },
+		ConsumeSound = "/SFX/MetaPointPickup",
+		AddResources =
+		{
+			MetaPoints = 20,
+		},
+		ConsumeFx = "MetaPointPickupFx",
+	},
+	RoomRewardMetaPointDropRunProgress =
+	{
+		InheritFrom = { "RoomRewardMetaPointDrop" },
+		CanDuplicate = false,
+	},
+
+	GemDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseGemDrop",
+		ConsumeSound = "/SFX/GemPickup",
+		AddResources =
+		{
+			Gems = 5,
+		},
+	},
+	GemDropRunProgress =
+	{
+		InheritFrom = { "GemDrop" },
+		CanDuplicate = false,
+	},
+
+	LockKeyDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseLockKeyDrop",
+		ConsumeSound = "/SFX/KeyPickup",
+		AddResources =
+		{
+			LockKeys = 1,
+		},
+	},
+	GiftDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseGiftDrop",
+		ConsumeSound = "/SFX/GiftAmbrosiaBottlePickup",
+		AddResources =
+		{
+			GiftPoints = 1,
+		},
+	},
+
+	SuperGemDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseSuperGemDrop",
+		ConsumeSound = "/SFX/SuperGemPickup",
+		AddResources =
+		{
+			SuperGems = 1,
+		},
+	},
+	SuperGiftDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseSuperGiftDrop",
+		ConsumeSound = "/SFX/SuperGiftAmbrosiaBottlePickup",
+		AddResources =
+		{
+			SuperGiftPoints = 1,
+		},
+	},
+	SuperLockKeyDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseSuperLockKeyDrop",
+		ConsumeSound = "/SFX/TitanBloodPickup",
+		AddResources =
+		{
+			SuperLockKeys = 1,
+		},
+	},
+
+	RoomRewardMoneyDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseMoneyDrop",
+		ConsumeSound = "/SFX/GoldCoinPickup",
+		AddMoney = 100,
+	},
+	MinorMoneyDrop =
+	{
+		InheritFrom = { "BaseConsumable" },
+		UseText = "UseMoneyDrop",
+		AddMoney = 5,
+	},
+	ChaosMoneyDrop =
+	{
+		InheritFrom = { "MinorMoneyDrop" },
+		AddMoney = 10,
+	},
+}
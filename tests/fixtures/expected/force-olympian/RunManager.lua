@@ -0,0 +1,20 @@
+-- excerpt of RunManager.lua
+function ChooseLoot( excludeLootNames, forceLootName )
+	local eligibleLootNames = GetEligibleLootNames( excludeLootNames )
+	local chosenLootName = forceLootName or ( Contains( eligibleLootNames, "ZeusUpgrade" ) and "ZeusUpgrade" ) or GetRandomValue( eligibleLootNames )
+	if chosenLootName == nil then
+		return nil
+	end
+	CurrentRun.LootTypeHistory[chosenLootName] = ( CurrentRun.LootTypeHistory[chosenLootName] or 0 ) + 1
+	return chosenLootName
+end
+
+function GetEligibleLootNames( excludeLootNames )
+	local eligibleLootNames = {}
+	for lootName, lootData in pairs( LootData ) do
+		if lootData.GodLoot and not Contains( excludeLootNames, lootName ) and IsGameStateEligible( CurrentRun, lootData ) then
+			table.insert( eligibleLootNames, lootName )
+		end
+	end
+	return eligibleLootNames
+end
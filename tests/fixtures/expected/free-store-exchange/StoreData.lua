@@ -0,0 +1,21 @@
+-- excerpt of StoreData.lua
+StoreData =
+{
+	MetaUpgradeExchange =
+	{
+		{
+			Name = "ExchangeKeysForDiamond",
+			CostName = "LockKeys",
+			CostAmount = -30,
+			BuyName = "SuperGems",
+			BuyAmount = 1,
+		},
+		{
+			Name = "ExchangeNectarForAmbrosia",
+			CostName = "GiftPoints",
+			CostAmount = -10,
+			BuyName = "SuperGiftPoints",
+			BuyAmount = 1,
+		},
+	},
+}
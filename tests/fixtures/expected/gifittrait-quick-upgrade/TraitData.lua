@@ -0,0 +1,25 @@
+-- excerpt of TraitData.lua
+TraitData =
+{
+	MaxHealthKeepsakeTrait =
+	{
+		Icon = "Keepsake_Collar",
+		InheritFrom = { "GiftTrait" },
+		InRackTitle = "MaxHealthKeepsakeTrait_Rack",
+		ChamberThresholds =  { 1, 1 },
+		PropertyChanges =
+		{
+			{
+				LuaProperty = "MaxHealth",
+				BaseValue = 25,
+				ChangeType = "Add",
+			},
+		},
+	},
+	DirectionalArmorTrait =
+	{
+		Icon = "Keepsake_Shield",
+		InheritFrom = { "GiftTrait" },
+		ChamberThresholds =  { 1, 1 },
+	},
+}
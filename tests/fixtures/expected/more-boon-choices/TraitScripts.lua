@@ -0,0 +1,39 @@
+-- excerpt of TraitScripts.lua
+function SetTraitsOnLoot( lootData, args )
+	local rarityTable = GetRarityTable( lootData )
+	for i, upgradeData in ipairs( lootData.UpgradeOptions ) do
+		if rarityTable.Epic[upgradeData.ItemName] and lootData.RarityChances.Epic and RandomChance( lootData.RarityChances.Epic ) then
+			upgradeData.Rarity = "Epic"
+		elseif rarityTable.Legendary[upgradeData.ItemName] and lootData.RarityChances.Legendary and RandomChance( lootData.RarityChances.Legendary ) then
+			upgradeData.Rarity = "Legendary"
+		end
+	end
+end
+
+function GetUpgradedRarity( lootData, validRarities, rarityTable )
+	local chosenRarity = "Common"
+	local chosenUpgrade = nil
+	if validRarities.Legendary and lootData.RarityChances.Legendary and RandomChance( lootData.RarityChances.Legendary ) then
+		chosenRarity = "Legendary"
+		chosenUpgrade = GetRandomValue( rarityTable.Legendary )
+	elseif validRarities.Heroic and lootData.RarityChances.Heroic and RandomChance( lootData.RarityChances.Heroic ) then
+		chosenRarity = "Heroic"
+		chosenUpgrade = GetRandomValue( rarityTable.Heroic )
+	end
+	if chosenUpgrade == nil then
+		return GetUpgradedRarityFallback( lootData, validRarities, rarityTable )
+	end
+	return chosenRarity, chosenUpgrade
+end
+
+function GetUpgradedRarityFallback( lootData, validRarities, rarityTable )
+	if validRarities.Rare and lootData.RarityChances.Rare then
+		chosenRarity = "Rare"
+		chosenUpgrade = GetRandomValue( rarityTable.Rare )
+	end
+	return chosenRarity, chosenUpgrade
+end
+
+function GetTotalLootChoices()
+	return 4 - GetNumMetaUpgrades( "ReducedLootChoicesShrineUpgrade" )
+end